@@ -0,0 +1,33 @@
+use crate::hooks::Hooks;
+use crate::webhook::WebhookConfig;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::PathBuf;
+
+/// Path of the user config file (e.g. `~/.config/stack-status/config.toml`)
+pub fn config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("stack-status").join("config.toml"))
+}
+
+/// User configuration, read from `config.toml`
+#[derive(Debug, Default, Deserialize)]
+pub struct Config {
+    #[serde(default)]
+    pub hooks: Hooks,
+    #[serde(default)]
+    pub webhook: WebhookConfig,
+}
+
+impl Config {
+    /// Load the config file, or defaults if there isn't one
+    pub fn load() -> Result<Self> {
+        let Some(path) = config_path().filter(|p| p.exists()) else {
+            return Ok(Self::default());
+        };
+
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("reading {}", path.display()))?;
+        toml::from_str(&contents).with_context(|| format!("parsing {}", path.display()))
+    }
+}
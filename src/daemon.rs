@@ -0,0 +1,213 @@
+use crate::{graphite, store, StackStatus};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// How long a client waits for the daemon before fetching on its own
+const QUERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long `--cached` output (prompts, status lines) waits for the daemon
+const CACHED_QUERY_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// A request sent to the daemon as one line of JSON
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Request {
+    /// Stack containing this branch (default: the daemon's current branch)
+    pub branch: Option<String>,
+}
+
+/// Socket the daemon for a checkout listens on (e.g. `$XDG_RUNTIME_DIR/stack-status/<hash>.sock`)
+///
+/// Named by a hash of the checkout path: two clones of one repo are separate
+/// stacks, and a full path could overflow the ~100 byte socket path limit.
+pub fn socket_path(toplevel: &str) -> Option<PathBuf> {
+    let dir = dirs::runtime_dir()
+        .map(|d| d.join("stack-status"))
+        .or_else(|| store::data_dir().map(|d| d.join("daemon")))?;
+    Some(dir.join(format!("{:016x}.sock", fnv1a(toplevel.as_bytes()))))
+}
+
+/// 64-bit FNV-1a, stable across builds unlike `DefaultHasher`
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Ask a running daemon for the stack containing `branch` (default: current branch)
+///
+/// Returns `None` when no daemon is running for this checkout or it hasn't
+/// cached that stack, so callers can fetch directly.
+pub async fn query(branch: Option<&str>) -> Option<StackStatus> {
+    query_within(branch, QUERY_TIMEOUT).await
+}
+
+/// Like `query`, but gives up quickly, for output that must never stall
+pub async fn query_cached(branch: Option<&str>) -> Option<StackStatus> {
+    query_within(branch, CACHED_QUERY_TIMEOUT).await
+}
+
+async fn query_within(branch: Option<&str>, timeout: Duration) -> Option<StackStatus> {
+    let (toplevel, current) = graphite::get_checkout().await?;
+    let path = socket_path(&toplevel)?;
+    if !path.exists() {
+        return None;
+    }
+
+    let request = Request {
+        branch: Some(branch.map_or(current, str::to_string)),
+    };
+    tokio::time::timeout(timeout, unix::request(&path, &request))
+        .await
+        .ok()?
+        .ok()?
+}
+
+/// Poll the current checkout's stack and serve it over a Unix socket until interrupted
+pub async fn run(interval: u64, has_gt: bool, has_gh: bool) -> Result<()> {
+    let Some(toplevel) = graphite::get_toplevel().await else {
+        anyhow::bail!("the daemon must run inside a git checkout");
+    };
+    let Some(path) = socket_path(&toplevel) else {
+        anyhow::bail!("no runtime or data directory for the daemon socket");
+    };
+    unix::serve(&path, interval, has_gt, has_gh).await
+}
+
+#[cfg(unix)]
+mod unix {
+    use super::Request;
+    use crate::StackStatus;
+    use anyhow::{bail, Result};
+    use std::path::Path;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::{UnixListener, UnixStream};
+    use tokio::signal::unix::{signal, SignalKind};
+    use tokio::sync::RwLock;
+
+    /// The last successful fetch and when it finished
+    type Cache = Arc<RwLock<Option<(Instant, StackStatus)>>>;
+
+    /// Polls that may fail in a row before the cache is no longer served
+    const STALE_AFTER_POLLS: u32 = 3;
+
+    /// Send a request; `None` means the daemon has no cached status for it
+    pub async fn request(path: &Path, request: &Request) -> Result<Option<StackStatus>> {
+        let stream = UnixStream::connect(path).await?;
+        let (reader, mut writer) = stream.into_split();
+
+        writer
+            .write_all(format!("{}\n", serde_json::to_string(request)?).as_bytes())
+            .await?;
+
+        let mut line = String::new();
+        BufReader::new(reader).read_line(&mut line).await?;
+        Ok(serde_json::from_str(&line)?)
+    }
+
+    pub async fn serve(path: &Path, interval: u64, has_gt: bool, has_gh: bool) -> Result<()> {
+        if path.exists() {
+            if UnixStream::connect(path).await.is_ok() {
+                bail!("a daemon is already serving {}", path.display());
+            }
+            // Left behind by a daemon that didn't shut down cleanly
+            std::fs::remove_file(path)?;
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let listener = UnixListener::bind(path)?;
+        eprintln!("stack-status daemon listening on {}", path.display());
+
+        let cache: Cache = Arc::new(RwLock::new(None));
+        let interval = Duration::from_secs(interval.max(1));
+        let poller = tokio::spawn(poll(cache.clone(), interval, has_gt, has_gh));
+        let mut terminate = signal(SignalKind::terminate())?;
+
+        let result = loop {
+            tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => {
+                        tokio::spawn(handle(stream, cache.clone(), interval * STALE_AFTER_POLLS));
+                    }
+                    Err(e) => break Err(e.into()),
+                },
+                _ = tokio::signal::ctrl_c() => break Ok(()),
+                _ = terminate.recv() => break Ok(()),
+            }
+        };
+
+        poller.abort();
+        std::fs::remove_file(path).ok();
+        result
+    }
+
+    async fn poll(cache: Cache, interval: Duration, has_gt: bool, has_gh: bool) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match crate::fetch_stack_status(None, has_gt, has_gh).await {
+                Ok(status) => *cache.write().await = Some((Instant::now(), status)),
+                Err(e) => eprintln!("stack-status daemon: fetch failed: {:#}", e),
+            }
+        }
+    }
+
+    /// Answer one request from the cache, or with `null` when the stack isn't cached
+    /// or the last successful fetch is older than `max_age`
+    ///
+    /// Never fetches: clients are waiting on the reply and can fetch themselves.
+    async fn handle(stream: UnixStream, cache: Cache, max_age: Duration) -> Result<()> {
+        let (reader, mut writer) = stream.into_split();
+        let mut line = String::new();
+        BufReader::new(reader).read_line(&mut line).await?;
+        let request: Request = serde_json::from_str(line.trim()).unwrap_or_default();
+
+        let cached = cache
+            .read()
+            .await
+            .clone()
+            .filter(|(fetched, _)| fetched.elapsed() <= max_age)
+            .map(|(_, status)| status)
+            .filter(|status| {
+                request
+                    .branch
+                    .as_ref()
+                    .is_none_or(|b| status.branches.iter().any(|s| &s.branch == b))
+            });
+
+        let status = cached.map(|mut status| {
+            if let Some(ref branch) = request.branch {
+                for b in &mut status.branches {
+                    b.is_focus = &b.branch == branch;
+                }
+            }
+            status
+        });
+
+        writer
+            .write_all(format!("{}\n", serde_json::to_string(&status)?).as_bytes())
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(not(unix))]
+mod unix {
+    use super::Request;
+    use crate::StackStatus;
+    use anyhow::{bail, Result};
+    use std::path::Path;
+
+    pub async fn request(_path: &Path, _request: &Request) -> Result<Option<StackStatus>> {
+        bail!("the daemon needs Unix domain sockets")
+    }
+
+    pub async fn serve(_path: &Path, _interval: u64, _has_gt: bool, _has_gh: bool) -> Result<()> {
+        bail!("the daemon needs Unix domain sockets")
+    }
+}
@@ -1,8 +1,28 @@
-use crate::github::CheckStatus;
+use crate::github::{
+    CheckStatus, Deployment, DeploymentState, MergeQueueState, MergeQueueStatus, MergeState, MergeStatus, PrState,
+    ReviewDecision, ReviewStatus,
+};
+use crate::eta;
+use crate::graphite::LocalSync;
+use crate::history::TimelineEntry;
+use crate::stats::StatsReport;
+use crate::ready::{Blocker, ReadyReport};
 use crate::StackStatus;
 use anyhow::Result;
 use std::io::{self, Write};
 
+/// How a one-shot status is printed
+#[derive(Debug, Clone, Copy, Default, PartialEq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Full stack view
+    #[default]
+    Text,
+    /// One line with tmux color styles, for `status-right`
+    Tmux,
+    /// Markdown table with PR and log links, for PRs, chat and standups
+    Markdown,
+}
+
 // ANSI escape codes
 const RESET: &str = "\x1b[0m";
 const BOLD: &str = "\x1b[1m";
@@ -119,6 +139,155 @@ fn render_progress_bar(completed: usize, total: usize, width: usize) -> String {
     )
 }
 
+/// Render review state shown next to the PR number
+fn render_review_badge(review: &ReviewStatus) -> String {
+    let decision = match review.decision {
+        Some(ReviewDecision::Approved) => {
+            format!(" {}✓ approved{}", GREEN, RESET)
+        }
+        Some(ReviewDecision::ChangesRequested) => format!(
+            " {}✗ changes requested by {}{}",
+            RED,
+            review.changes_requested.join(", "),
+            RESET
+        ),
+        Some(ReviewDecision::ReviewRequired) => {
+            format!(" {}◌ review required{}", YELLOW, RESET)
+        }
+        None => String::new(),
+    };
+
+    // Partial approvals are worth showing while the decision is still open
+    let approvals = match review.approvals.len() {
+        0 => String::new(),
+        _ if review.decision == Some(ReviewDecision::Approved) => String::new(),
+        1 => format!(" {}1 approval{}", GREEN, RESET),
+        n => format!(" {}{} approvals{}", GREEN, n, RESET),
+    };
+
+    let pending = if review.pending.is_empty() {
+        String::new()
+    } else {
+        format!(" {}awaiting {}{}", DIM, review.pending.join(", "), RESET)
+    };
+
+    format!("{}{}{}", decision, approvals, pending)
+}
+
+/// Render a mergeability badge so blocked or conflicting PRs stand out
+fn render_merge_badge(merge: &MergeStatus) -> String {
+    let (label, color) = if merge.has_conflicts {
+        ("conflicts", RED)
+    } else {
+        match merge.state {
+            MergeState::Clean | MergeState::HasHooks => ("mergeable", GREEN),
+            MergeState::Behind => ("behind base", YELLOW),
+            MergeState::Blocked => ("blocked", YELLOW),
+            MergeState::Unstable => ("unstable", YELLOW),
+            MergeState::Dirty => ("conflicts", RED),
+            MergeState::Draft | MergeState::Unknown => return String::new(),
+        }
+    };
+    format!(" {}[{}]{}", color, label, RESET)
+}
+
+/// Render the merge queue line shown under a queued PR's status
+fn render_merge_queue(queue: &MergeQueueStatus, frame: usize) -> String {
+    let (state, color) = match queue.state {
+        MergeQueueState::Queued => ("queued", CYAN),
+        MergeQueueState::AwaitingChecks => ("awaiting checks", YELLOW),
+        MergeQueueState::Mergeable => ("merging", GREEN),
+        MergeQueueState::Unmergeable => ("unmergeable", RED),
+        MergeQueueState::Locked => ("locked", GRAY),
+        MergeQueueState::Unknown => ("unknown", GRAY),
+    };
+
+    let checks = if queue.summary.total == 0 {
+        String::new()
+    } else {
+        let color = match queue.summary.overall {
+            CheckStatus::Passed => GREEN,
+            CheckStatus::Failed => RED,
+            CheckStatus::Running => YELLOW,
+            _ => GRAY,
+        };
+        let icon = match queue.summary.overall {
+            CheckStatus::Running => progress_spinner(frame),
+            other => other.icon(),
+        };
+        format!(
+            " {}·{} merge_group {}{} {}{}",
+            DIM, RESET, color, icon, queue.summary.text(), RESET
+        )
+    };
+
+    format!(
+        "{}⇢ Merge queue #{}{} {}{}{}{}",
+        CYAN, queue.position, RESET, color, state, RESET, checks
+    )
+}
+
+/// Render the deployments section shown under a branch's checks box
+fn render_deployments(deployments: &[Deployment], frame: usize) {
+    println!("    {}Deployments{}", DIM, RESET);
+
+    for deployment in deployments {
+        let (icon, color, label) = match deployment.state {
+            DeploymentState::Success => ("✓", GREEN, "deployed"),
+            DeploymentState::Failure | DeploymentState::Error => ("✗", RED, "failed"),
+            DeploymentState::InProgress => (spinner(frame), YELLOW, "deploying"),
+            DeploymentState::Queued | DeploymentState::Pending => ("○", GRAY, "queued"),
+            DeploymentState::Waiting => ("○", YELLOW, "waiting"),
+            DeploymentState::Inactive => ("◌", GRAY, "inactive"),
+            DeploymentState::Unknown => ("?", GRAY, "unknown"),
+        };
+
+        let url = deployment
+            .url
+            .as_deref()
+            .or(deployment.log_url.as_deref())
+            .map(|u| format!("  {}{}{}", CYAN, u, RESET))
+            .unwrap_or_default();
+
+        println!(
+            "    {}{}{} {:<20} {}{}{}{}",
+            color, icon, RESET, deployment.environment, color, label, RESET, url
+        );
+    }
+}
+
+/// Warn when displayed checks don't describe the local branch tip
+fn render_local_sync(sync: &LocalSync, pr_sha: Option<&str>) -> Option<String> {
+    let mut notes = Vec::new();
+
+    match sync.unpushed {
+        Some(0) | None => {}
+        Some(1) => notes.push("1 unpushed commit".to_string()),
+        Some(n) => notes.push(format!("{} unpushed commits", n)),
+    }
+
+    match sync.behind {
+        Some(0) | None => {}
+        Some(1) => notes.push("1 commit behind the PR".to_string()),
+        Some(n) => notes.push(format!("{} commits behind the PR", n)),
+    }
+
+    if sync.stale_checks {
+        let short = |sha: &str| sha.chars().take(7).collect::<String>();
+        notes.push(format!(
+            "checks ran on {} (local {})",
+            pr_sha.map(short).unwrap_or_default(),
+            short(&sync.local_sha)
+        ));
+    }
+
+    if notes.is_empty() {
+        None
+    } else {
+        Some(format!("{}⚠ {}{}", YELLOW, notes.join(" · "), RESET))
+    }
+}
+
 /// Render simple mode (non-watch, no animation)
 pub fn render(status: &StackStatus, show_details: bool) {
     render_with_frame(status, show_details, 0);
@@ -126,10 +295,11 @@ pub fn render(status: &StackStatus, show_details: bool) {
 
 /// Render with animation frame for watch mode
 pub fn render_with_frame(status: &StackStatus, show_details: bool, frame: usize) {
+    let now = chrono::Utc::now();
     let (term_width, _term_height) = get_terminal_size();
-    let width = term_width.min(100).max(60);
+    let width = term_width.clamp(60, 100);
     let box_width = (width - 6).min(80);
-    let name_width = (width - 30).min(50).max(25);
+    let name_width = (width - 30).clamp(25, 50);
 
     // Header box
     println!(
@@ -165,7 +335,7 @@ pub fn render_with_frame(status: &StackStatus, show_details: bool, frame: usize)
         // Branch indicator with color
         let (indicator, indicator_color) = if branch.is_trunk {
             ("●", GRAY)
-        } else if branch.is_current {
+        } else if branch.is_focus {
             ("◉", BLUE)
         } else {
             ("◯", DIM)
@@ -177,6 +347,19 @@ pub fn render_with_frame(status: &StackStatus, show_details: bool, frame: usize)
             .map(|n| format!(" {}#{}{}", CYAN, n, RESET))
             .unwrap_or_default();
 
+        // Review state next to the PR number
+        let review_info = branch
+            .review
+            .as_ref()
+            .map(render_review_badge)
+            .unwrap_or_default();
+
+        let merge_info = branch
+            .merge
+            .as_ref()
+            .map(render_merge_badge)
+            .unwrap_or_default();
+
         // Overall status indicator (animated for running)
         let status_str = if let Some(ref summary) = branch.summary {
             match summary.overall {
@@ -209,10 +392,29 @@ pub fn render_with_frame(status: &StackStatus, show_details: bool, frame: usize)
             }
         } else if branch.is_trunk {
             String::new()
+        } else if branch.pr_state == Some(PrState::Closed) {
+            format!("{}⚠ PR closed without merging{}", YELLOW, RESET)
         } else {
             format!("{}— No PR{}", DIM, RESET)
         };
 
+        let threads_info = match branch.unresolved_threads {
+            Some(0) | None => String::new(),
+            Some(1) => format!(" {}1 unresolved thread{}", YELLOW, RESET),
+            Some(n) => format!(" {}{} unresolved threads{}", YELLOW, n, RESET),
+        };
+
+        let queue_info = match branch.merge_queue {
+            Some(ref q) => format!(" {}[queued #{}]{}", CYAN, q.position, RESET),
+            None => String::new(),
+        };
+
+        let draft_info = if branch.pr_state == Some(PrState::Draft) {
+            format!(" {}[draft]{}", DIM, RESET)
+        } else {
+            String::new()
+        };
+
         // Full branch name (or truncate if really long)
         let branch_display = if branch.branch.len() > name_width {
             format!("{}…", &branch.branch[..name_width - 1])
@@ -220,16 +422,46 @@ pub fn render_with_frame(status: &StackStatus, show_details: bool, frame: usize)
             branch.branch.clone()
         };
 
+        // Point out the checked-out branch when viewing another branch's stack
+        let current_hint = if branch.is_current && !branch.is_focus {
+            format!(" {}(current){}", DIM, RESET)
+        } else {
+            String::new()
+        };
+
+        // Merged branches collapse to a single dim line
+        if branch.pr_state == Some(PrState::Merged) {
+            println!(
+                "{}{} {} #{} merged{}{}",
+                DIM,
+                indicator,
+                branch_display,
+                branch.pr.unwrap_or_default(),
+                RESET,
+                current_hint,
+            );
+            if !is_last {
+                println!("{}  │{}", DIM, RESET);
+            }
+            continue;
+        }
+
         // Print branch line
         println!(
-            "{}{}{} {}{}{}{}",
+            "{}{}{} {}{}{}{}{}{}{}{}{}{}",
             indicator_color,
             indicator,
             RESET,
-            if branch.is_current { BOLD } else { "" },
+            if branch.is_focus { BOLD } else { "" },
             branch_display,
-            if branch.is_current { RESET } else { "" },
+            if branch.is_focus { RESET } else { "" },
             pr_info,
+            draft_info,
+            queue_info,
+            merge_info,
+            review_info,
+            threads_info,
+            current_hint,
         );
 
         // Status on next line, indented
@@ -237,6 +469,18 @@ pub fn render_with_frame(status: &StackStatus, show_details: bool, frame: usize)
             println!("    {}", status_str);
         }
 
+        if let Some(warning) = branch
+            .local_sync
+            .as_ref()
+            .and_then(|sync| render_local_sync(sync, branch.head_sha.as_deref()))
+        {
+            println!("    {}", warning);
+        }
+
+        if let Some(ref queue) = branch.merge_queue {
+            println!("    {}", render_merge_queue(queue, frame));
+        }
+
         // Always show checks if we have them (details mode shows more info per check)
         if !branch.is_trunk && branch.checks.is_some() {
             if let Some(ref checks) = branch.checks {
@@ -250,8 +494,12 @@ pub fn render_with_frame(status: &StackStatus, show_details: bool, frame: usize)
                     );
 
                     for check in checks {
+                        // Failures of known-flaky checks are likely noise, not breakage
+                        let flaky = check.flaky.filter(|_| check.status == CheckStatus::Failed);
+
                         let (icon, color) = match check.status {
                             CheckStatus::Passed => ("✓", GREEN),
+                            CheckStatus::Failed if flaky.is_some() => ("✗", YELLOW),
                             CheckStatus::Failed => ("✗", RED),
                             CheckStatus::Running => (spinner(frame), YELLOW),
                             CheckStatus::Queued => ("○", GRAY),
@@ -270,13 +518,20 @@ pub fn render_with_frame(status: &StackStatus, show_details: bool, frame: usize)
 
                         // Duration or status indicator
                         let timing = match check.status {
+                            CheckStatus::Failed if flaky.is_some() => {
+                                format!("flaky {:.0}%", flaky.unwrap_or(0.0) * 100.0)
+                            }
                             CheckStatus::Passed | CheckStatus::Failed => {
                                 check.duration_secs
-                                    .map(|d| format_duration(d))
+                                    .map(format_duration)
                                     .unwrap_or_else(|| "—".to_string())
                             }
                             CheckStatus::Running => {
-                                "running…".to_string()
+                                match eta::check_remaining_secs(check, now) {
+                                    Some(0) => "overdue".to_string(),
+                                    Some(left) => format!("~{} left", format_duration(left)),
+                                    None => "running…".to_string(),
+                                }
                             }
                             CheckStatus::Queued => "queued".to_string(),
                             CheckStatus::Skipped => "skipped".to_string(),
@@ -287,6 +542,7 @@ pub fn render_with_frame(status: &StackStatus, show_details: bool, frame: usize)
                         // Status label
                         let status_label = match check.status {
                             CheckStatus::Passed => format!("{}passed{}", GREEN, RESET),
+                            CheckStatus::Failed if flaky.is_some() => format!("{}FAILED{}", YELLOW, RESET),
                             CheckStatus::Failed => format!("{}FAILED{}", RED, RESET),
                             CheckStatus::Running => format!("{}running{}", YELLOW, RESET),
                             CheckStatus::Queued => format!("{}queued{}", GRAY, RESET),
@@ -322,22 +578,34 @@ pub fn render_with_frame(status: &StackStatus, show_details: bool, frame: usize)
                             let total = summary.total;
                             let bar_width = (box_width - 20).min(40);
 
+                            // Weight the bar by expected check time when history allows
+                            let (bar, label) = match branch.eta {
+                                Some(ref eta) => (
+                                    render_progress_bar(eta.done_secs as usize, eta.total_secs as usize, bar_width),
+                                    format!(
+                                        "{}/{} · ~{} left",
+                                        completed, total, format_duration(eta.remaining_secs)
+                                    ),
+                                ),
+                                None => (
+                                    render_progress_bar(completed, total, bar_width),
+                                    format!("{}/{} complete", completed, total),
+                                ),
+                            };
+
                             println!(
-                                "    {}{}{}{}{}",
+                                "    {}{}{}{}{}{}{}",
                                 DIM, BOX_V, RESET,
                                 " ".repeat(box_width - 2),
-                                format!("{}{}{}", DIM, BOX_V, RESET)
+                                DIM, BOX_V, RESET
                             );
-                            let padding = if box_width > bar_width + 22 {
-                                " ".repeat(box_width - bar_width - 22)
-                            } else {
-                                String::new()
-                            };
+                            let used = bar_width + label.chars().count() + 5;
+                            let padding = " ".repeat(box_width.saturating_sub(used));
                             println!(
-                                "    {}{}{} {} {}/{} complete {}{}{}{}",
+                                "    {}{}{} {} {} {}{}{}{}",
                                 DIM, BOX_V, RESET,
-                                render_progress_bar(completed, total, bar_width),
-                                completed, total,
+                                bar,
+                                label,
                                 padding,
                                 DIM, BOX_V, RESET
                             );
@@ -353,12 +621,31 @@ pub fn render_with_frame(status: &StackStatus, show_details: bool, frame: usize)
             }
         }
 
+        if let Some(ref deployments) = branch.deployments {
+            if !deployments.is_empty() {
+                println!();
+                render_deployments(deployments, frame);
+            }
+        }
+
         // Connector line (except for last item)
         if !is_last {
             println!("{}  │{}", DIM, RESET);
         }
     }
 
+    // Whole-stack estimate from recorded check durations
+    if let Some(eta) = status.eta_secs {
+        let done_at = chrono::Local::now() + chrono::Duration::seconds(eta as i64);
+        println!();
+        println!(
+            "  {}Stack ETA:{} ~{} {}(done ≈ {}){}",
+            BOLD, RESET,
+            format_duration(eta),
+            DIM, done_at.format("%H:%M"), RESET
+        );
+    }
+
     println!();
 }
 
@@ -387,3 +674,208 @@ pub fn render_complete_message() {
         GREEN, RESET
     );
 }
+
+/// Render the bottom-up merge readiness report
+pub fn render_ready_report(report: &ReadyReport) {
+    println!("{}Merge readiness{} {}(bottom → top){}", BOLD, RESET, DIM, RESET);
+    println!();
+
+    for branch in &report.branches {
+        let pr = branch.pr.map(|n| format!(" #{}", n)).unwrap_or_default();
+
+        if branch.landed {
+            println!("  {}✓ {}{} merged{}", DIM, branch.branch, pr, RESET);
+            continue;
+        }
+
+        if branch.landable {
+            println!(
+                "  {}✓{} {}{}{}{}  {}ready{}",
+                GREEN, RESET, branch.branch, CYAN, pr, RESET, GREEN, RESET
+            );
+            continue;
+        }
+
+        // PRs that only wait on their parent aren't the interesting blocker
+        let waiting_only = branch
+            .blockers
+            .iter()
+            .all(|b| matches!(b, Blocker::ParentNotLanded { .. }));
+        let (icon, color) = if waiting_only { ("·", DIM) } else { ("✗", RED) };
+
+        println!(
+            "  {}{}{} {}{}{}{}",
+            color, icon, RESET, branch.branch, CYAN, pr, RESET
+        );
+        for blocker in &branch.blockers {
+            println!("      {}- {}{}", DIM, blocker.describe(), RESET);
+        }
+    }
+
+    println!();
+    let open = report.branches.iter().filter(|b| !b.landed).count();
+    match report.first_blocked() {
+        Some(blocked) => println!(
+            "  {}{} of {}{} PRs landable now. First blocker: {}{}{}",
+            BOLD,
+            report.landable,
+            open,
+            RESET,
+            RED,
+            blocked.branch,
+            RESET
+        ),
+        None if open == 0 => println!("  {}Nothing left to land.{}", DIM, RESET),
+        None => println!("  {}✓ All {} PRs landable now{}", GREEN, open, RESET),
+    }
+}
+
+/// Render a branch's recorded check history, grouped by pushed commit
+pub fn render_history(branch: &str, timeline: &[TimelineEntry]) {
+    let pr = timeline
+        .iter()
+        .rev()
+        .find_map(|e| e.pr)
+        .map(|n| format!(" {}#{}{}", CYAN, n, RESET))
+        .unwrap_or_default();
+    println!("{}History for {}{}{}", BOLD, branch, RESET, pr);
+    println!();
+
+    if timeline.is_empty() {
+        println!("  {}No recorded history yet. Run stack-status on this branch first.{}", DIM, RESET);
+        println!();
+        return;
+    }
+
+    let mut last_sha: Option<&str> = None;
+    for entry in timeline {
+        let sha = entry.head_sha.as_deref();
+        if sha != last_sha || last_sha.is_none() {
+            let short: String = sha.unwrap_or("unknown").chars().take(7).collect();
+            println!("  {}{}{}", CYAN, short, RESET);
+            last_sha = sha;
+        }
+
+        let when = chrono::DateTime::parse_from_rfc3339(&entry.fetched_at)
+            .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|_| entry.fetched_at.clone());
+
+        let (icon, color) = match entry.summary.overall {
+            CheckStatus::Passed => ("✓", GREEN),
+            CheckStatus::Failed => ("✗", RED),
+            CheckStatus::Running | CheckStatus::Queued => ("◐", YELLOW),
+            _ => ("○", GRAY),
+        };
+
+        let failed = if entry.failed.is_empty() {
+            String::new()
+        } else {
+            format!(" {}({}){}", DIM, entry.failed.join(", "), RESET)
+        };
+
+        println!(
+            "    {}{}{}  {}{} {}{}{}",
+            DIM, when, RESET, color, icon, entry.summary.text(), RESET, failed
+        );
+    }
+    println!();
+}
+
+/// Render per-check CI statistics as a table
+pub fn render_stats(report: &StatsReport) {
+    let window = report
+        .since
+        .as_deref()
+        .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+        .map(|t| format!(" since {}", t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")))
+        .unwrap_or_default();
+    println!("{}CI statistics{}{}{}{}", BOLD, RESET, DIM, window, RESET);
+    println!();
+
+    if report.checks.is_empty() {
+        println!("  {}No recorded check runs match.{}", DIM, RESET);
+        println!();
+        return;
+    }
+
+    let name_width = report
+        .checks
+        .iter()
+        .map(|c| c.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .clamp(5, 40);
+
+    println!(
+        "  {}{:<name_width$}  {:>5}  {:>6}  {:>8}  {:>8}  {:>8}  {:>8}{}",
+        DIM, "check", "runs", "pass", "median", "p90", "queue", "CI min", RESET,
+    );
+
+    let secs = |s: Option<u64>| s.map(format_duration).unwrap_or_else(|| "—".to_string());
+    for check in &report.checks {
+        let name: String = check.name.chars().take(name_width).collect();
+        let (rate, color) = match check.pass_rate {
+            Some(r) if r >= 0.95 => (format!("{:.0}%", r * 100.0), GREEN),
+            Some(r) if r >= 0.8 => (format!("{:.0}%", r * 100.0), YELLOW),
+            Some(r) => (format!("{:.0}%", r * 100.0), RED),
+            None => ("—".to_string(), GRAY),
+        };
+
+        println!(
+            "  {:<name_width$}  {:>5}  {}{:>6}{}  {:>8}  {:>8}  {:>8}  {:>8.1}",
+            name,
+            check.runs,
+            color,
+            rate,
+            RESET,
+            secs(check.median_secs),
+            secs(check.p90_secs),
+            secs(check.median_queue_secs),
+            check.ci_secs as f64 / 60.0,
+        );
+    }
+
+    println!();
+    println!(
+        "  {}Total CI time:{} {:.1} min",
+        BOLD,
+        RESET,
+        report.total_ci_secs as f64 / 60.0
+    );
+    println!();
+}
+
+/// One-line stack summary with tmux styles, bottom of the stack first
+///
+/// e.g. `#[fg=green]#245 ✓ #[fg=yellow]#246 ◐3/5 #[fg=red]#247 ✗1#[default]`
+pub fn tmux_line(status: &StackStatus) -> String {
+    let segments: Vec<String> = status
+        .branches
+        .iter()
+        .rev()
+        .filter(|b| !b.is_trunk)
+        .filter_map(|b| {
+            let pr = b.pr?;
+            let (color, state) = match b.pr_state {
+                Some(PrState::Merged) => ("colour244", "merged".to_string()),
+                Some(PrState::Closed) => ("colour244", "closed".to_string()),
+                _ => match b.summary.as_ref() {
+                    Some(s) if s.overall == CheckStatus::Failed => ("red", format!("✗{}", s.failed)),
+                    Some(s) if matches!(s.overall, CheckStatus::Running | CheckStatus::Queued) => {
+                        ("yellow", format!("◐{}/{}", s.passed, s.total))
+                    }
+                    Some(s) if s.overall == CheckStatus::Passed => ("green", "✓".to_string()),
+                    _ => ("default", "·".to_string()),
+                },
+            };
+            let focus = if b.is_focus { ",bold" } else { "" };
+            Some(format!("#[fg={}{}]#{} {}#[nobold]", color, focus, pr, state))
+        })
+        .collect();
+
+    if segments.is_empty() {
+        String::new()
+    } else {
+        format!("{}#[default]", segments.join(" "))
+    }
+}
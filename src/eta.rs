@@ -0,0 +1,141 @@
+use crate::github::{Check, CheckStatus};
+use crate::store::DurationHistory;
+use crate::StackStatus;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Time-based progress estimate for a branch's checks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchEta {
+    /// Estimated seconds until the slowest remaining check finishes
+    pub remaining_secs: u64,
+    /// Check time already spent, weighted by each check's typical duration
+    pub done_secs: u64,
+    /// Total expected check time for the branch
+    pub total_secs: u64,
+}
+
+/// Fill in expected durations and ETAs from recorded history
+///
+/// ETAs are only set when every unfinished check has history; a guess that
+/// ignores an unknown job would be worse than no guess.
+pub fn apply(status: &mut StackStatus, history: &DurationHistory, now: DateTime<Utc>) {
+    let mut stack_remaining: Option<u64> = None;
+    let mut stack_known = true;
+
+    for branch in &mut status.branches {
+        let Some(ref mut checks) = branch.checks else {
+            continue;
+        };
+
+        let mut remaining = 0;
+        let mut done = 0;
+        let mut total = 0;
+        let mut pending = false;
+        let mut known = true;
+
+        for check in checks.iter_mut() {
+            check.expected_secs = history.expected_secs(&check.name);
+
+            match check.status {
+                CheckStatus::Running | CheckStatus::Queued => {
+                    pending = true;
+                    let Some(expected) = check.expected_secs else {
+                        known = false;
+                        continue;
+                    };
+
+                    let left = check_remaining_secs(check, now).unwrap_or(expected);
+                    remaining = remaining.max(left);
+                    done += expected - left;
+                    total += expected;
+                }
+                CheckStatus::Passed | CheckStatus::Failed => {
+                    let secs = check.duration_secs.or(check.expected_secs).unwrap_or(0);
+                    done += secs;
+                    total += secs;
+                }
+                _ => {}
+            }
+        }
+
+        branch.eta = if pending && known {
+            stack_remaining = Some(stack_remaining.unwrap_or(0).max(remaining));
+            Some(BranchEta {
+                remaining_secs: remaining,
+                done_secs: done,
+                total_secs: total,
+            })
+        } else {
+            if pending {
+                stack_known = false;
+            }
+            None
+        };
+    }
+
+    status.eta_secs = stack_remaining.filter(|_| stack_known);
+}
+
+/// Estimated seconds left for an unfinished check with recorded history
+///
+/// Overdue checks report zero rather than going negative.
+pub fn check_remaining_secs(check: &Check, now: DateTime<Utc>) -> Option<u64> {
+    let expected = check.expected_secs?;
+    let elapsed = match check.status {
+        CheckStatus::Running => elapsed_secs(check.started_at.as_deref(), now).unwrap_or(0),
+        CheckStatus::Queued => 0,
+        _ => return None,
+    };
+    Some(expected.saturating_sub(elapsed))
+}
+
+fn elapsed_secs(started_at: Option<&str>, now: DateTime<Utc>) -> Option<u64> {
+    let started = DateTime::parse_from_rfc3339(started_at?).ok()?;
+    Some((now - started.with_timezone(&Utc)).num_seconds().max(0) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn check(name: &str, status: CheckStatus, started_at: Option<&str>, secs: Option<u64>) -> Check {
+        Check {
+            started_at: started_at.map(String::from),
+            duration_secs: secs,
+            url: Some(format!("https://ci/{}", name)),
+            ..testing::check(name, status)
+        }
+    }
+
+    #[test]
+    fn test_long_running_check_dominates_progress() {
+        let mut history = DurationHistory::in_memory("acme/app");
+        history.record(&[
+            check("lint", CheckStatus::Passed, None, Some(60)),
+            check("integration", CheckStatus::Passed, None, Some(2400)),
+        ]);
+
+        let now = DateTime::parse_from_rfc3339("2024-01-01T12:10:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let mut status = StackStatus::new();
+        status.branches.push(testing::branch(
+            "feature",
+            vec![
+                check("lint", CheckStatus::Passed, None, Some(60)),
+                check("integration", CheckStatus::Running, Some("2024-01-01T12:00:00Z"), None),
+            ],
+        ));
+
+        apply(&mut status, &history, now);
+
+        let eta = status.branches[0].eta.as_ref().unwrap();
+        assert_eq!(eta.remaining_secs, 1800);
+        assert_eq!(eta.done_secs, 660);
+        assert_eq!(eta.total_secs, 2460);
+        assert_eq!(status.eta_secs, Some(1800));
+    }
+}
@@ -0,0 +1,260 @@
+use crate::github::{CheckStatus, PrState, ReviewDecision};
+use crate::{BranchStatus, StackStatus};
+use serde::Serialize;
+
+/// Something that changed between two consecutive status fetches
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    BranchAdded { branch: String },
+    BranchRemoved { branch: String },
+    PrOpened { branch: String, pr: u64 },
+    PrApproved { branch: String, pr: u64 },
+    PrMerged { branch: String, pr: u64 },
+    CheckStarted(CheckEvent),
+    CheckPassed(CheckEvent),
+    CheckFailed(CheckEvent),
+    CheckCancelled(CheckEvent),
+    /// Every check on a branch finished and none failed
+    ChecksPassed {
+        branch: String,
+        pr: Option<u64>,
+        total: usize,
+    },
+    /// Every check on a branch finished and some failed
+    ChecksFailed {
+        branch: String,
+        pr: Option<u64>,
+        failed: Vec<String>,
+    },
+    /// No check in the stack is running or queued any more
+    StackSettled { failing: Vec<String> },
+    /// The stack's aggregate check status changed
+    StackStatusChanged { from: CheckStatus, to: CheckStatus },
+}
+
+/// A single check changing state on a branch
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CheckEvent {
+    pub branch: String,
+    pub pr: Option<u64>,
+    pub check: String,
+    pub url: Option<String>,
+}
+
+/// Compare two snapshots of the stack and describe what changed
+pub fn diff(prev: &StackStatus, next: &StackStatus) -> Vec<Event> {
+    let mut events = Vec::new();
+
+    for branch in next.branches.iter().filter(|b| !b.is_trunk) {
+        match prev.branches.iter().find(|b| b.branch == branch.branch) {
+            Some(before) => diff_branch(before, branch, &mut events),
+            None => events.push(Event::BranchAdded {
+                branch: branch.branch.clone(),
+            }),
+        }
+    }
+
+    for branch in prev.branches.iter().filter(|b| !b.is_trunk) {
+        if !next.branches.iter().any(|b| b.branch == branch.branch) {
+            events.push(Event::BranchRemoved {
+                branch: branch.branch.clone(),
+            });
+        }
+    }
+
+    let (from, to) = (prev.overall(), next.overall());
+    if from != to {
+        events.push(Event::StackStatusChanged { from, to });
+    }
+
+    if !prev.all_complete() && next.all_complete() {
+        events.push(Event::StackSettled {
+            failing: next
+                .branches
+                .iter()
+                .filter(|b| b.summary.as_ref().is_some_and(|s| s.failed > 0))
+                .map(|b| b.branch.clone())
+                .collect(),
+        });
+    }
+
+    events
+}
+
+fn diff_branch(before: &BranchStatus, after: &BranchStatus, events: &mut Vec<Event>) {
+    let name = &after.branch;
+
+    if let Some(pr) = after.pr {
+        if before.pr.is_none() {
+            events.push(Event::PrOpened {
+                branch: name.clone(),
+                pr,
+            });
+        }
+
+        let approved = |b: &BranchStatus| {
+            b.review
+                .as_ref()
+                .is_some_and(|r| r.decision == Some(ReviewDecision::Approved))
+        };
+        if approved(after) && !approved(before) {
+            events.push(Event::PrApproved {
+                branch: name.clone(),
+                pr,
+            });
+        }
+
+        if after.pr_state == Some(PrState::Merged) && before.pr_state != Some(PrState::Merged) {
+            events.push(Event::PrMerged {
+                branch: name.clone(),
+                pr,
+            });
+        }
+    }
+
+    for check in after.checks.iter().flatten() {
+        let was = before
+            .checks
+            .iter()
+            .flatten()
+            .find(|c| c.name == check.name)
+            .map(|c| c.status);
+        if was == Some(check.status) {
+            continue;
+        }
+
+        let event = CheckEvent {
+            branch: name.clone(),
+            pr: after.pr,
+            check: check.name.clone(),
+            url: check.url.clone(),
+        };
+        match check.status {
+            CheckStatus::Running => events.push(Event::CheckStarted(event)),
+            CheckStatus::Passed => events.push(Event::CheckPassed(event)),
+            CheckStatus::Failed => events.push(Event::CheckFailed(event)),
+            CheckStatus::Cancelled => events.push(Event::CheckCancelled(event)),
+            _ => {}
+        }
+    }
+
+    // Overall reads Failed as soon as one check fails, so go by the counts:
+    // the branch is done once nothing is running or queued
+    let pending = |b: &BranchStatus| b.summary.as_ref().is_some_and(|s| s.running + s.queued > 0);
+    let finished = pending(before) && !pending(after);
+    if let Some(summary) = after.summary.as_ref().filter(|_| finished) {
+        match summary.overall {
+            CheckStatus::Passed => events.push(Event::ChecksPassed {
+                branch: name.clone(),
+                pr: after.pr,
+                total: summary.total,
+            }),
+            CheckStatus::Failed => events.push(Event::ChecksFailed {
+                branch: name.clone(),
+                pr: after.pr,
+                failed: after
+                    .checks
+                    .iter()
+                    .flatten()
+                    .filter(|c| c.status == CheckStatus::Failed)
+                    .map(|c| c.name.clone())
+                    .collect(),
+            }),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn branch(name: &str, pr: Option<u64>, checks: &[(&str, CheckStatus)]) -> BranchStatus {
+        BranchStatus {
+            pr,
+            ..testing::branch(name, testing::checks(checks))
+        }
+    }
+
+    fn status(branches: Vec<BranchStatus>) -> StackStatus {
+        let mut status = StackStatus::new();
+        status.branches = branches;
+        status
+    }
+
+    #[test]
+    fn test_diff_emits_typed_events() {
+        let prev = status(vec![
+            branch("a", None, &[("lint", CheckStatus::Queued)]),
+            branch("b", Some(2), &[("test", CheckStatus::Running)]),
+            branch("old", Some(3), &[]),
+        ]);
+        let next = status(vec![
+            branch("a", Some(1), &[("lint", CheckStatus::Running)]),
+            branch("b", Some(2), &[("test", CheckStatus::Failed)]),
+            branch("new", None, &[]),
+        ]);
+
+        let check = |branch: &str, pr, name: &str| CheckEvent {
+            branch: branch.to_string(),
+            pr,
+            check: name.to_string(),
+            url: None,
+        };
+
+        assert_eq!(
+            diff(&prev, &next),
+            vec![
+                Event::PrOpened {
+                    branch: "a".to_string(),
+                    pr: 1
+                },
+                Event::CheckStarted(check("a", Some(1), "lint")),
+                Event::CheckFailed(check("b", Some(2), "test")),
+                Event::ChecksFailed {
+                    branch: "b".to_string(),
+                    pr: Some(2),
+                    failed: vec!["test".to_string()]
+                },
+                Event::BranchAdded {
+                    branch: "new".to_string()
+                },
+                Event::BranchRemoved {
+                    branch: "old".to_string()
+                },
+                Event::StackStatusChanged {
+                    from: CheckStatus::Running,
+                    to: CheckStatus::Failed
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_checks_failed_waits_for_running_checks() {
+        use CheckStatus::*;
+        let stack = |lint, test| {
+            status(vec![branch(
+                "c",
+                Some(4),
+                &[("lint", lint), ("test", test)],
+            )])
+        };
+        let (running, one_failed, done) = (
+            stack(Running, Running),
+            stack(Failed, Running),
+            stack(Failed, Passed),
+        );
+
+        let checks_failed = |events: Vec<Event>| {
+            events
+                .into_iter()
+                .filter(|e| matches!(e, Event::ChecksFailed { .. }))
+                .count()
+        };
+        assert_eq!(checks_failed(diff(&running, &one_failed)), 0);
+        assert_eq!(checks_failed(diff(&one_failed, &done)), 1);
+    }
+}
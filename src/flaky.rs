@@ -0,0 +1,97 @@
+use crate::github::CheckStatus;
+use crate::store::Snapshot;
+use crate::StackStatus;
+use std::collections::HashMap;
+
+/// Outcomes a check has had on each head SHA
+#[derive(Debug, Default)]
+struct Outcomes {
+    passed: bool,
+    failed: bool,
+}
+
+/// Flakiness confidence per check name, from 0 to 1
+///
+/// A check that both failed and passed on the same head SHA flipped on
+/// identical code. Confidence is the share of SHAs where it failed that also
+/// saw it pass, damped so a single flip doesn't read as certain.
+pub fn scores(snapshots: &[Snapshot]) -> HashMap<String, f64> {
+    let mut outcomes: HashMap<(&str, &str), Outcomes> = HashMap::new();
+
+    for branch in snapshots.iter().flat_map(|s| &s.branches) {
+        let Some(ref sha) = branch.head_sha else {
+            continue;
+        };
+        for check in &branch.checks {
+            let entry = outcomes.entry((&check.name, sha)).or_default();
+            match check.status {
+                CheckStatus::Passed => entry.passed = true,
+                CheckStatus::Failed => entry.failed = true,
+                _ => {}
+            }
+        }
+    }
+
+    // Per check: SHAs it failed on, and how many of those it also passed on
+    let mut counts: HashMap<&str, (u32, u32)> = HashMap::new();
+    for (&(name, _), o) in outcomes.iter().filter(|(_, o)| o.failed) {
+        let (failed, flips) = counts.entry(name).or_default();
+        *failed += 1;
+        if o.passed {
+            *flips += 1;
+        }
+    }
+
+    counts
+        .into_iter()
+        .filter(|&(_, (_, flips))| flips > 0)
+        .map(|(name, (failed, flips))| {
+            let confidence = flips as f64 / (failed as f64 + 1.0);
+            (name.to_string(), (confidence * 100.0).round() / 100.0)
+        })
+        .collect()
+}
+
+/// Mark checks in the status with their flakiness score, if any
+pub fn apply(status: &mut StackStatus, snapshots: &[Snapshot]) {
+    let scores = scores(snapshots);
+    if scores.is_empty() {
+        return;
+    }
+
+    for check in status
+        .branches
+        .iter_mut()
+        .filter_map(|b| b.checks.as_mut())
+        .flatten()
+    {
+        check.flaky = scores.get(&check.name).copied();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn snapshot(sha: &str, checks: &[(&str, CheckStatus)]) -> Snapshot {
+        testing::snapshot("", sha, testing::checks(checks))
+    }
+
+    #[test]
+    fn test_scores_flag_flips_on_same_sha() {
+        let snapshots = vec![
+            // e2e fails then passes on a retry of the same commit, twice
+            snapshot("aaa", &[("e2e", CheckStatus::Failed), ("lint", CheckStatus::Failed)]),
+            snapshot("aaa", &[("e2e", CheckStatus::Passed), ("lint", CheckStatus::Failed)]),
+            snapshot("bbb", &[("e2e", CheckStatus::Failed), ("lint", CheckStatus::Passed)]),
+            snapshot("bbb", &[("e2e", CheckStatus::Passed), ("lint", CheckStatus::Passed)]),
+            snapshot("ccc", &[("e2e", CheckStatus::Passed), ("lint", CheckStatus::Passed)]),
+        ];
+
+        let scores = scores(&snapshots);
+        // lint failed on aaa and was fixed by a new commit: a real failure
+        assert_eq!(scores.get("lint"), None);
+        assert_eq!(scores.get("e2e"), Some(&0.67));
+    }
+}
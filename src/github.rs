@@ -30,6 +30,584 @@ pub async fn get_pr_for_branch(branch: &str) -> Option<u64> {
         .ok()
 }
 
+/// Fields requested from `gh pr view` for each branch's PR
+const PR_FIELDS: &str = "number,reviewDecision,latestReviews,reviewRequests,mergeable,mergeStateStatus,state,isDraft,headRefOid,author,url";
+
+/// Raw PR data from gh CLI
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawPullRequest {
+    number: u64,
+    review_decision: Option<String>,
+    #[serde(default)]
+    latest_reviews: Vec<RawReview>,
+    #[serde(default)]
+    review_requests: Vec<RawReviewRequest>,
+    mergeable: Option<String>,
+    merge_state_status: Option<String>,
+    state: Option<String>,
+    #[serde(default)]
+    is_draft: bool,
+    head_ref_oid: Option<String>,
+    author: Option<RawActor>,
+    url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawReview {
+    author: Option<RawActor>,
+    state: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawActor {
+    login: String,
+}
+
+/// A requested reviewer is either a user (login) or a team (name)
+#[derive(Debug, Deserialize)]
+struct RawReviewRequest {
+    login: Option<String>,
+    name: Option<String>,
+}
+
+/// Normalized PR information
+#[derive(Debug, Clone)]
+pub struct PullRequest {
+    pub number: u64,
+    pub state: PrState,
+    /// Commit the PR's checks ran against
+    pub head_sha: Option<String>,
+    /// Login of the PR author
+    pub author: Option<String>,
+    pub url: Option<String>,
+    pub review: ReviewStatus,
+    pub merge: MergeStatus,
+}
+
+/// Lifecycle state of a PR
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PrState {
+    Open,
+    Draft,
+    Merged,
+    Closed,
+}
+
+impl PrState {
+    /// Merged and closed PRs won't run CI again, so their checks needn't be polled
+    pub fn is_settled(&self) -> bool {
+        matches!(self, PrState::Merged | PrState::Closed)
+    }
+}
+
+/// Review state of a PR
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReviewStatus {
+    pub decision: Option<ReviewDecision>,
+    pub approvals: Vec<String>,
+    pub changes_requested: Vec<String>,
+    pub pending: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewDecision {
+    Approved,
+    ChangesRequested,
+    ReviewRequired,
+}
+
+/// Mergeability of a PR against its base branch
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MergeStatus {
+    /// `None` while GitHub is still computing mergeability
+    pub mergeable: Option<bool>,
+    pub state: MergeState,
+    pub behind_base: bool,
+    pub has_conflicts: bool,
+}
+
+/// GitHub's `mergeStateStatus`
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeState {
+    Clean,
+    Behind,
+    Blocked,
+    Dirty,
+    Draft,
+    HasHooks,
+    Unstable,
+    Unknown,
+}
+
+/// Get PR details (number, review and merge state) for a branch
+pub async fn get_pr(branch: &str) -> Option<PullRequest> {
+    let output = Command::new("gh")
+        .args(["pr", "view", branch, "--json", PR_FIELDS])
+        .output()
+        .await
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let raw: RawPullRequest = serde_json::from_slice(&output.stdout).ok()?;
+    Some(normalize_pr(raw))
+}
+
+fn normalize_pr(raw: RawPullRequest) -> PullRequest {
+    let decision = match raw.review_decision.as_deref() {
+        Some("APPROVED") => Some(ReviewDecision::Approved),
+        Some("CHANGES_REQUESTED") => Some(ReviewDecision::ChangesRequested),
+        Some("REVIEW_REQUIRED") => Some(ReviewDecision::ReviewRequired),
+        _ => None,
+    };
+
+    let reviewers_in_state = |state: &str| -> Vec<String> {
+        raw.latest_reviews
+            .iter()
+            .filter(|r| r.state == state)
+            .filter_map(|r| r.author.as_ref().map(|a| a.login.clone()))
+            .collect()
+    };
+
+    let review = ReviewStatus {
+        decision,
+        approvals: reviewers_in_state("APPROVED"),
+        changes_requested: reviewers_in_state("CHANGES_REQUESTED"),
+        pending: raw
+            .review_requests
+            .iter()
+            .filter_map(|r| r.login.clone().or_else(|| r.name.clone()))
+            .collect(),
+    };
+
+    let mergeable = match raw.mergeable.as_deref() {
+        Some("MERGEABLE") => Some(true),
+        Some("CONFLICTING") => Some(false),
+        _ => None,
+    };
+
+    let merge_state = match raw.merge_state_status.as_deref() {
+        Some("CLEAN") => MergeState::Clean,
+        Some("BEHIND") => MergeState::Behind,
+        Some("BLOCKED") => MergeState::Blocked,
+        Some("DIRTY") => MergeState::Dirty,
+        Some("DRAFT") => MergeState::Draft,
+        Some("HAS_HOOKS") => MergeState::HasHooks,
+        Some("UNSTABLE") => MergeState::Unstable,
+        _ => MergeState::Unknown,
+    };
+
+    let merge = MergeStatus {
+        mergeable,
+        state: merge_state,
+        behind_base: merge_state == MergeState::Behind,
+        has_conflicts: mergeable == Some(false) || merge_state == MergeState::Dirty,
+    };
+
+    let state = match raw.state.as_deref() {
+        Some("MERGED") => PrState::Merged,
+        Some("CLOSED") => PrState::Closed,
+        _ if raw.is_draft => PrState::Draft,
+        _ => PrState::Open,
+    };
+
+    PullRequest {
+        number: raw.number,
+        state,
+        head_sha: raw.head_ref_oid,
+        author: raw.author.map(|a| a.login),
+        url: raw.url,
+        review,
+        merge,
+    }
+}
+
+/// Run a GraphQL query through `gh api graphql`
+///
+/// `{owner}` and `{repo}` are always passed as variables and filled in by gh
+/// from the current repository.
+async fn graphql(query: &str, variables: &[(&str, String)]) -> Option<serde_json::Value> {
+    let mut args = vec![
+        "api".to_string(),
+        "graphql".to_string(),
+        "-f".to_string(),
+        format!("query={}", query),
+        "-F".to_string(),
+        "owner={owner}".to_string(),
+        "-F".to_string(),
+        "repo={repo}".to_string(),
+    ];
+    for (name, value) in variables {
+        args.push("-F".to_string());
+        args.push(format!("{}={}", name, value));
+    }
+
+    let output = Command::new("gh").args(&args).output().await.ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let response: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+    response.get("data").cloned()
+}
+
+const PR_ACTIVITY_QUERY: &str = "query($owner: String!, $repo: String!, $number: Int!) {
+  repository(owner: $owner, name: $repo) {
+    pullRequest(number: $number) {
+      reviewThreads(first: 100) { nodes { isResolved } pageInfo { hasNextPage endCursor } }
+      commits(last: 1) {
+        nodes {
+          commit {
+            deployments(first: 20, orderBy: {field: CREATED_AT, direction: DESC}) {
+              nodes { environment state latestStatus { state environmentUrl logUrl } }
+            }
+          }
+        }
+      }
+      mergeQueueEntry {
+        position
+        state
+        headCommit {
+          checkSuites(first: 50) {
+            nodes {
+              checkRuns(first: 100) {
+                nodes { name status conclusion detailsUrl startedAt completedAt }
+              }
+            }
+          }
+        }
+      }
+    }
+  }
+}";
+
+/// Further pages of review threads, for PRs with more than the first query's 100
+const REVIEW_THREADS_QUERY: &str = "query($owner: String!, $repo: String!, $number: Int!, $after: String!) {
+  repository(owner: $owner, name: $repo) {
+    pullRequest(number: $number) {
+      reviewThreads(first: 100, after: $after) { nodes { isResolved } pageInfo { hasNextPage endCursor } }
+    }
+  }
+}";
+
+/// Review conversation, merge queue and deployment state of a PR, fetched with GraphQL
+#[derive(Debug, Clone)]
+pub struct PrActivity {
+    pub unresolved_threads: u64,
+    pub merge_queue: Option<MergeQueueStatus>,
+    /// Latest deployment per environment for the PR head commit
+    pub deployments: Vec<Deployment>,
+}
+
+/// A deployment of the PR head, e.g. a preview or staging environment
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Deployment {
+    pub environment: String,
+    pub state: DeploymentState,
+    pub url: Option<String>,
+    pub log_url: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeploymentState {
+    Success,
+    Failure,
+    Error,
+    InProgress,
+    Queued,
+    Pending,
+    Waiting,
+    Inactive,
+    Unknown,
+}
+
+/// A PR's entry in the GitHub merge queue
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MergeQueueStatus {
+    /// 1-based position in the queue
+    pub position: u64,
+    pub state: MergeQueueState,
+    /// Checks running on the `merge_group` commit
+    pub checks: Vec<Check>,
+    pub summary: CheckSummary,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeQueueState {
+    Queued,
+    AwaitingChecks,
+    Mergeable,
+    Unmergeable,
+    Locked,
+    Unknown,
+}
+
+/// Get unresolved review thread count and merge queue state for a PR
+pub async fn get_pr_activity(pr: u64) -> Option<PrActivity> {
+    let data = graphql(PR_ACTIVITY_QUERY, &[("number", pr.to_string())]).await?;
+    let mut activity = parse_pr_activity(&data)?;
+
+    let mut cursor = next_page(data.pointer("/repository/pullRequest/reviewThreads"));
+    while let Some(after) = cursor {
+        let variables = [("number", pr.to_string()), ("after", after)];
+        let page = graphql(REVIEW_THREADS_QUERY, &variables).await?;
+        let threads = page.pointer("/repository/pullRequest/reviewThreads");
+        activity.unresolved_threads += threads.map_or(0, count_unresolved);
+        cursor = next_page(threads);
+    }
+    Some(activity)
+}
+
+fn count_unresolved(threads: &serde_json::Value) -> u64 {
+    threads
+        .get("nodes")
+        .and_then(|n| n.as_array())
+        .map_or(0, |nodes| {
+            nodes
+                .iter()
+                .filter(|t| t.get("isResolved").and_then(|r| r.as_bool()) == Some(false))
+                .count() as u64
+        })
+}
+
+/// Cursor for the next page of a connection, if there is one
+fn next_page(connection: Option<&serde_json::Value>) -> Option<String> {
+    let page_info = connection?.get("pageInfo")?;
+    if page_info.get("hasNextPage").and_then(|h| h.as_bool()) != Some(true) {
+        return None;
+    }
+    page_info.get("endCursor")?.as_str().map(str::to_string)
+}
+
+fn parse_pr_activity(data: &serde_json::Value) -> Option<PrActivity> {
+    let pr = data.pointer("/repository/pullRequest")?;
+
+    let unresolved_threads = count_unresolved(pr.get("reviewThreads")?);
+
+    let merge_queue = pr
+        .get("mergeQueueEntry")
+        .filter(|e| !e.is_null())
+        .map(parse_merge_queue_entry);
+
+    let deployments = pr
+        .pointer("/commits/nodes/0/commit/deployments/nodes")
+        .and_then(|n| n.as_array())
+        .map(|nodes| parse_deployments(nodes))
+        .unwrap_or_default();
+
+    Some(PrActivity {
+        unresolved_threads,
+        merge_queue,
+        deployments,
+    })
+}
+
+/// Keep the newest deployment per environment (input is newest first)
+fn parse_deployments(nodes: &[serde_json::Value]) -> Vec<Deployment> {
+    let mut deployments: Vec<Deployment> = Vec::new();
+
+    for node in nodes {
+        let Some(environment) = node.get("environment").and_then(|e| e.as_str()) else {
+            continue;
+        };
+        if deployments.iter().any(|d| d.environment == environment) {
+            continue;
+        }
+
+        let status = node.get("latestStatus").filter(|s| !s.is_null());
+        let state = status
+            .and_then(|s| s.get("state"))
+            .or_else(|| node.get("state"))
+            .and_then(|s| s.as_str());
+        let url_field = |field: &str| {
+            status
+                .and_then(|s| s.get(field))
+                .and_then(|u| u.as_str())
+                .filter(|u| !u.is_empty())
+                .map(String::from)
+        };
+
+        deployments.push(Deployment {
+            environment: environment.to_string(),
+            state: match state {
+                Some("SUCCESS" | "ACTIVE") => DeploymentState::Success,
+                Some("FAILURE") => DeploymentState::Failure,
+                Some("ERROR") => DeploymentState::Error,
+                Some("IN_PROGRESS") => DeploymentState::InProgress,
+                Some("QUEUED") => DeploymentState::Queued,
+                Some("PENDING") => DeploymentState::Pending,
+                Some("WAITING") => DeploymentState::Waiting,
+                Some("INACTIVE" | "DESTROYED") => DeploymentState::Inactive,
+                _ => DeploymentState::Unknown,
+            },
+            url: url_field("environmentUrl"),
+            log_url: url_field("logUrl"),
+        });
+    }
+
+    deployments
+}
+
+fn parse_merge_queue_entry(entry: &serde_json::Value) -> MergeQueueStatus {
+    let state = match entry.get("state").and_then(|s| s.as_str()) {
+        Some("QUEUED") => MergeQueueState::Queued,
+        Some("AWAITING_CHECKS") => MergeQueueState::AwaitingChecks,
+        Some("MERGEABLE") => MergeQueueState::Mergeable,
+        Some("UNMERGEABLE") => MergeQueueState::Unmergeable,
+        Some("LOCKED") => MergeQueueState::Locked,
+        _ => MergeQueueState::Unknown,
+    };
+
+    let runs = entry
+        .pointer("/headCommit/checkSuites/nodes")
+        .and_then(|n| n.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|suite| suite.pointer("/checkRuns/nodes")?.as_array())
+        .flatten();
+
+    let checks: Vec<Check> = runs
+        .filter_map(|run| serde_json::from_value::<RawCheckRun>(run.clone()).ok())
+        .map(normalize_check_run)
+        .collect();
+
+    MergeQueueStatus {
+        position: entry.get("position").and_then(|p| p.as_u64()).unwrap_or(0),
+        state,
+        summary: summarize_checks(&checks),
+        checks,
+    }
+}
+
+/// Raw check run from the GraphQL API
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawCheckRun {
+    name: String,
+    status: Option<String>,
+    conclusion: Option<String>,
+    details_url: Option<String>,
+    started_at: Option<String>,
+    completed_at: Option<String>,
+}
+
+/// Map a GraphQL check run onto the same buckets `gh pr checks` uses
+fn normalize_check_run(run: RawCheckRun) -> Check {
+    let bucket = match (run.status.as_deref(), run.conclusion.as_deref()) {
+        (Some("COMPLETED"), Some("SUCCESS" | "NEUTRAL")) => "pass",
+        (Some("COMPLETED"), Some("SKIPPED")) => "skipping",
+        (Some("COMPLETED"), Some("CANCELLED")) => "cancel",
+        (Some("COMPLETED"), _) => "fail",
+        _ => "pending",
+    };
+
+    normalize_check(RawCheck {
+        name: run.name,
+        state: run.status,
+        conclusion: run.conclusion.map(|c| c.to_lowercase()),
+        started_at: run.started_at,
+        completed_at: run.completed_at,
+        details_url: run.details_url,
+        bucket: Some(bucket.to_string()),
+    })
+}
+
+/// How `gh pr merge` should merge a PR
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum MergeMethod {
+    Squash,
+    Merge,
+    Rebase,
+}
+
+impl MergeMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MergeMethod::Squash => "squash",
+            MergeMethod::Merge => "merge",
+            MergeMethod::Rebase => "rebase",
+        }
+    }
+}
+
+/// Merge a PR with gh
+pub async fn merge_pr(pr: u64, method: MergeMethod) -> Result<()> {
+    let output = Command::new("gh")
+        .args([
+            "pr",
+            "merge",
+            &pr.to_string(),
+            &format!("--{}", method.as_str()),
+        ])
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "gh pr merge #{} failed: {}",
+            pr,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(())
+}
+
+/// Get the base branch a branch's PR targets
+pub async fn get_pr_base(branch: &str) -> Option<String> {
+    let output = Command::new("gh")
+        .args(["pr", "view", branch, "--json", "baseRefName", "--jq", ".baseRefName"])
+        .output()
+        .await
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let base = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if base.is_empty() {
+        None
+    } else {
+        Some(base)
+    }
+}
+
+/// Get head branches of open PRs that target `base`
+pub async fn get_pr_heads_for_base(base: &str) -> Vec<String> {
+    let output = Command::new("gh")
+        .args([
+            "pr",
+            "list",
+            "--base",
+            base,
+            "--json",
+            "headRefName",
+            "--jq",
+            ".[].headRefName",
+        ])
+        .output()
+        .await;
+
+    match output {
+        Ok(o) if o.status.success() => String::from_utf8_lossy(&o.stdout)
+            .lines()
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
 /// Raw check data from gh CLI
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -44,16 +622,22 @@ struct RawCheck {
 }
 
 /// Normalized check information
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Check {
     pub name: String,
     pub status: CheckStatus,
     pub conclusion: Option<String>,
+    pub started_at: Option<String>,
     pub duration_secs: Option<u64>,
+    /// Typical duration from recorded history, if any
+    pub expected_secs: Option<u64>,
+    /// Flakiness confidence (0-1) from recorded flips on identical commits
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flaky: Option<f64>,
     pub url: Option<String>,
 }
 
-#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, schemars::JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum CheckStatus {
     Passed,
@@ -113,6 +697,33 @@ pub async fn get_checks(branch: &str) -> Result<Vec<Check>> {
     Ok(raw_checks.into_iter().map(normalize_check).collect())
 }
 
+const REQUIRED_CHECKS_QUERY: &str = "query($owner: String!, $repo: String!, $number: Int!) {
+  repository(owner: $owner, name: $repo) {
+    pullRequest(number: $number) {
+      baseRef { branchProtectionRule { requiredStatusCheckContexts } }
+    }
+  }
+}";
+
+/// Get the check names branch protection requires on a PR's base branch
+///
+/// Read from the protection rule rather than `gh pr checks --required`, which
+/// only lists checks that have already reported. Returns an empty list when
+/// the base branch isn't protected, and `None` when the query failed.
+pub async fn get_required_check_names(pr: u64) -> Option<Vec<String>> {
+    let data = graphql(REQUIRED_CHECKS_QUERY, &[("number", pr.to_string())]).await?;
+    Some(required_contexts(&data))
+}
+
+fn required_contexts(data: &serde_json::Value) -> Vec<String> {
+    data.pointer("/repository/pullRequest/baseRef/branchProtectionRule/requiredStatusCheckContexts")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str().map(String::from))
+        .collect()
+}
+
 fn normalize_check(raw: RawCheck) -> Check {
     let status = match raw.bucket.as_deref() {
         Some("pass") => CheckStatus::Passed,
@@ -145,13 +756,16 @@ fn normalize_check(raw: RawCheck) -> Check {
         name: raw.name,
         status,
         conclusion: raw.conclusion,
+        started_at: raw.started_at,
         duration_secs,
+        expected_secs: None,
+        flaky: None,
         url: raw.details_url,
     }
 }
 
 /// Summary of check statuses
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CheckSummary {
     pub total: usize,
     pub passed: usize,
@@ -234,3 +848,112 @@ pub async fn get_pr_url(branch: &str) -> Option<String> {
         Some(url)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_pr() {
+        let raw: RawPullRequest = serde_json::from_str(
+            r#"{
+                "number": 247,
+                "reviewDecision": "CHANGES_REQUESTED",
+                "latestReviews": [
+                    {"author": {"login": "alice"}, "state": "APPROVED"},
+                    {"author": {"login": "bob"}, "state": "CHANGES_REQUESTED"},
+                    {"author": {"login": "carol"}, "state": "COMMENTED"}
+                ],
+                "reviewRequests": [
+                    {"__typename": "User", "login": "dave"},
+                    {"__typename": "Team", "name": "platform", "slug": "platform"}
+                ],
+                "mergeable": "CONFLICTING",
+                "mergeStateStatus": "DIRTY",
+                "state": "OPEN",
+                "isDraft": true
+            }"#,
+        )
+        .unwrap();
+
+        let pr = normalize_pr(raw);
+        assert_eq!(pr.number, 247);
+        assert_eq!(pr.state, PrState::Draft);
+        assert_eq!(pr.review.decision, Some(ReviewDecision::ChangesRequested));
+        assert_eq!(pr.review.approvals, ["alice"]);
+        assert_eq!(pr.review.changes_requested, ["bob"]);
+        assert_eq!(pr.review.pending, ["dave", "platform"]);
+        assert_eq!(pr.merge.state, MergeState::Dirty);
+        assert!(pr.merge.has_conflicts);
+        assert!(!pr.merge.behind_base);
+    }
+
+    #[test]
+    fn test_parse_pr_activity_with_merge_queue() {
+        let data = serde_json::json!({
+            "repository": {"pullRequest": {
+                "reviewThreads": {"nodes": [{"isResolved": true}, {"isResolved": false}]},
+                "mergeQueueEntry": {
+                    "position": 2,
+                    "state": "AWAITING_CHECKS",
+                    "headCommit": {"checkSuites": {"nodes": [{"checkRuns": {"nodes": [
+                        {"name": "test", "status": "IN_PROGRESS", "conclusion": null},
+                        {"name": "lint", "status": "COMPLETED", "conclusion": "SUCCESS"}
+                    ]}}]}}
+                }
+            }}
+        });
+
+        let activity = parse_pr_activity(&data).unwrap();
+        assert_eq!(activity.unresolved_threads, 1);
+        assert_eq!(next_page(data.pointer("/repository/pullRequest/reviewThreads")), None);
+
+        let queue = activity.merge_queue.unwrap();
+        assert_eq!(queue.position, 2);
+        assert_eq!(queue.state, MergeQueueState::AwaitingChecks);
+        assert_eq!(queue.checks[0].status, CheckStatus::Running);
+        assert_eq!(queue.checks[1].status, CheckStatus::Passed);
+        assert_eq!(queue.summary.overall, CheckStatus::Running);
+        assert!(activity.deployments.is_empty());
+    }
+
+    #[test]
+    fn test_review_threads_next_page() {
+        let threads = serde_json::json!({
+            "nodes": [{"isResolved": false}, {"isResolved": false}],
+            "pageInfo": {"hasNextPage": true, "endCursor": "Y3Vyc29yOjEwMA=="}
+        });
+        assert_eq!(count_unresolved(&threads), 2);
+        assert_eq!(next_page(Some(&threads)).as_deref(), Some("Y3Vyc29yOjEwMA=="));
+    }
+
+    #[test]
+    fn test_parse_deployments_keeps_latest_per_environment() {
+        let nodes = serde_json::json!([
+            {"environment": "preview", "state": "ACTIVE",
+             "latestStatus": {"state": "SUCCESS", "environmentUrl": "https://pr-247.example.dev", "logUrl": ""}},
+            {"environment": "preview", "state": "INACTIVE", "latestStatus": null},
+            {"environment": "staging", "state": "IN_PROGRESS", "latestStatus": null}
+        ]);
+
+        let deployments = parse_deployments(nodes.as_array().unwrap());
+        assert_eq!(deployments.len(), 2);
+        assert_eq!(deployments[0].state, DeploymentState::Success);
+        assert_eq!(deployments[0].url.as_deref(), Some("https://pr-247.example.dev"));
+        assert_eq!(deployments[0].log_url, None);
+        assert_eq!(deployments[1].state, DeploymentState::InProgress);
+    }
+
+    #[test]
+    fn test_required_contexts() {
+        let protected = serde_json::json!({"repository": {"pullRequest": {"baseRef": {
+            "branchProtectionRule": {"requiredStatusCheckContexts": ["ci/test", "lint"]}
+        }}}});
+        assert_eq!(required_contexts(&protected), ["ci/test", "lint"]);
+
+        let unprotected = serde_json::json!({"repository": {"pullRequest": {"baseRef": {
+            "branchProtectionRule": null
+        }}}});
+        assert!(required_contexts(&unprotected).is_empty());
+    }
+}
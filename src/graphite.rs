@@ -1,5 +1,9 @@
 use crate::BranchInfo;
 use anyhow::Result;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
 use tokio::process::Command;
 
 /// Check if Graphite CLI (gt) is installed
@@ -44,6 +48,262 @@ pub async fn get_stack() -> Result<Vec<BranchInfo>> {
     Ok(parse_gt_log_short(&stdout))
 }
 
+/// How a local branch compares with the PR head commit CI ran on
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LocalSync {
+    pub local_sha: String,
+    /// Local commits the PR head doesn't have yet
+    pub unpushed: Option<u64>,
+    /// PR head commits the local branch doesn't have yet (e.g. pushed from elsewhere)
+    #[serde(default)]
+    pub behind: Option<u64>,
+    /// The local tip has commits CI hasn't seen, so the checks describe older code; also set
+    /// when the tips differ and the PR head hasn't been fetched to count them
+    pub stale_checks: bool,
+}
+
+/// Compare a local branch tip with its PR head SHA
+pub async fn compare_with_pr_head(branch: &str, pr_sha: &str) -> Option<LocalSync> {
+    let local_sha = git_output(&["rev-parse", "--verify", "--quiet", branch]).await?;
+
+    // Needs the PR head object locally; unknown if it hasn't been fetched
+    let counts = git_output(&[
+        "rev-list",
+        "--left-right",
+        "--count",
+        &format!("{}...{}", pr_sha, local_sha),
+    ])
+    .await;
+    let (behind, unpushed) = match counts.as_deref().and_then(|c| c.split_once('\t')) {
+        Some((behind, ahead)) => (behind.parse().ok(), ahead.parse().ok()),
+        None => (None, None),
+    };
+
+    Some(LocalSync {
+        stale_checks: match unpushed {
+            Some(n) => n > 0,
+            None => local_sha != pr_sha,
+        },
+        local_sha,
+        unpushed,
+        behind,
+    })
+}
+
+/// Tip commit of a local branch, or `None` if there's no such branch
+pub async fn branch_sha(branch: &str) -> Option<String> {
+    git_output(&["rev-parse", "--verify", "--quiet", &format!("refs/heads/{}", branch)]).await
+}
+
+/// Identify the current repository, preferring the origin remote URL
+pub async fn get_repo_id() -> Option<String> {
+    match git_output(&["config", "--get", "remote.origin.url"]).await {
+        Some(url) => Some(url.trim_end_matches(".git").to_string()),
+        None => git_output(&["rev-parse", "--show-toplevel"]).await,
+    }
+}
+
+/// Top-level directory of the current checkout
+pub async fn get_toplevel() -> Option<String> {
+    git_output(&["rev-parse", "--show-toplevel"]).await
+}
+
+/// Top-level directory and current branch of the checkout, in a single git call
+pub async fn get_checkout() -> Option<(String, String)> {
+    let out = git_output(&["rev-parse", "--show-toplevel", "--abbrev-ref", "HEAD"]).await?;
+    let (toplevel, branch) = out.split_once('\n')?;
+    Some((toplevel.to_string(), branch.trim().to_string()))
+}
+
+/// Run a git command and return its trimmed stdout, or `None` on failure
+async fn git_output(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().await.ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if stdout.is_empty() {
+        None
+    } else {
+        Some(stdout)
+    }
+}
+
+/// Sync trunk from remote and clean up merged branches, without prompting
+pub async fn sync() -> Result<()> {
+    run_gt(&["sync", "--force"]).await
+}
+
+/// Restack the current stack onto its updated parents
+pub async fn restack() -> Result<()> {
+    run_gt(&["restack"]).await
+}
+
+/// Push the restacked branches and update their PRs
+pub async fn submit() -> Result<()> {
+    run_gt(&["submit", "--stack", "--no-interactive"]).await
+}
+
+async fn run_gt(args: &[&str]) -> Result<()> {
+    let output = Command::new("gt").args(args).output().await?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "gt {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(())
+}
+
+/// Graphite's per-branch metadata, stored as a blob under `refs/branch-metadata/<branch>`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BranchMetadata {
+    parent_branch_name: Option<String>,
+}
+
+/// Get the stack containing an arbitrary branch from Graphite's metadata refs
+///
+/// Returns branches from top of stack to trunk, or an empty list if the branch
+/// is not tracked by Graphite. Only needs git, not the gt CLI.
+pub async fn get_stack_for_branch(branch: &str, current: &str) -> Result<Vec<BranchInfo>> {
+    let parents = get_branch_parents().await?;
+    if !parents.contains_key(branch) {
+        return Ok(Vec::new());
+    }
+    Ok(build_stack(branch, current, &parents))
+}
+
+/// Read the parent of every Graphite-tracked branch (branch -> parent)
+async fn get_branch_parents() -> Result<HashMap<String, String>> {
+    let output = Command::new("git")
+        .args([
+            "for-each-ref",
+            "--format=%(objectname) %(refname:strip=2)",
+            "refs/branch-metadata/",
+        ])
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        return Ok(HashMap::new());
+    }
+
+    let refs: Vec<(String, String)> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .map(|(oid, name)| (oid.to_string(), name.to_string()))
+        .collect();
+
+    if refs.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    // Read all metadata blobs with a single git process
+    let mut child = Command::new("git")
+        .args(["cat-file", "--batch"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        let oids: String = refs.iter().map(|(oid, _)| format!("{}\n", oid)).collect();
+        stdin.write_all(oids.as_bytes()).await?;
+    }
+
+    let output = child.wait_with_output().await?;
+    let blobs = parse_cat_file_batch(&output.stdout);
+
+    Ok(refs
+        .into_iter()
+        .zip(blobs)
+        .filter_map(|((_, name), blob)| {
+            let metadata: BranchMetadata = serde_json::from_slice(&blob).ok()?;
+            Some((name, metadata.parent_branch_name?))
+        })
+        .collect())
+}
+
+/// Split `git cat-file --batch` output into object contents, in request order
+fn parse_cat_file_batch(mut output: &[u8]) -> Vec<Vec<u8>> {
+    let mut blobs = Vec::new();
+
+    while let Some(newline) = output.iter().position(|&b| b == b'\n') {
+        let header = String::from_utf8_lossy(&output[..newline]).to_string();
+        output = &output[newline + 1..];
+
+        // Missing objects have no content section
+        let size = match header.rsplit(' ').next().and_then(|s| s.parse::<usize>().ok()) {
+            Some(size) if !header.ends_with(" missing") => size,
+            _ => {
+                blobs.push(Vec::new());
+                continue;
+            }
+        };
+
+        let size = size.min(output.len());
+        blobs.push(output[..size].to_vec());
+        output = &output[(size + 1).min(output.len())..];
+    }
+
+    blobs
+}
+
+/// Order the stack around `branch` like `gt log short`: descendants on top,
+/// then the branch itself, then its ancestors down to trunk
+fn build_stack(branch: &str, current: &str, parents: &HashMap<String, String>) -> Vec<BranchInfo> {
+    let mut children: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (child, parent) in parents {
+        children.entry(parent.as_str()).or_default().push(child.as_str());
+    }
+    for list in children.values_mut() {
+        list.sort();
+    }
+
+    fn collect_descendants<'a>(
+        branch: &'a str,
+        children: &HashMap<&'a str, Vec<&'a str>>,
+        out: &mut Vec<String>,
+    ) {
+        for &child in children.get(branch).into_iter().flatten() {
+            if out.iter().any(|b| b == child) {
+                continue;
+            }
+            collect_descendants(child, children, out);
+            out.push(child.to_string());
+        }
+    }
+
+    let mut names = Vec::new();
+    collect_descendants(branch, &children, &mut names);
+    names.push(branch.to_string());
+
+    // Walk parents until reaching a branch without metadata (trunk)
+    let mut cursor = branch;
+    while let Some(parent) = parents.get(cursor) {
+        if names.contains(parent) {
+            break;
+        }
+        names.push(parent.clone());
+        cursor = parent;
+    }
+    let trunk = names.last().filter(|name| !parents.contains_key(*name)).cloned();
+
+    names
+        .into_iter()
+        .map(|name| BranchInfo {
+            is_current: name == current,
+            is_trunk: Some(&name) == trunk.as_ref(),
+            name,
+        })
+        .collect()
+}
+
 /// Parse gt log short output into structured data
 ///
 /// Example outputs from gt log short:
@@ -128,4 +388,30 @@ mod tests {
         assert_eq!(branches[3].name, "main");
         assert!(branches[3].is_trunk);
     }
+
+    #[test]
+    fn test_build_stack_for_other_branch() {
+        let parents: HashMap<String, String> = [
+            ("feature-a", "main"),
+            ("feature-b", "feature-a"),
+            ("feature-c", "feature-b"),
+            ("other", "main"),
+        ]
+        .into_iter()
+        .map(|(c, p)| (c.to_string(), p.to_string()))
+        .collect();
+
+        let branches = build_stack("feature-b", "other", &parents);
+        let names: Vec<_> = branches.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, ["feature-c", "feature-b", "feature-a", "main"]);
+        assert!(branches[3].is_trunk);
+        assert!(branches.iter().all(|b| !b.is_current));
+    }
+
+    #[test]
+    fn test_parse_cat_file_batch() {
+        let output = b"abc blob 5\nhello\ndef missing\n123 blob 2\nhi\n";
+        let blobs = parse_cat_file_batch(output);
+        assert_eq!(blobs, vec![b"hello".to_vec(), Vec::new(), b"hi".to_vec()]);
+    }
 }
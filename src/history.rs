@@ -0,0 +1,80 @@
+use crate::github::{self, CheckStatus, CheckSummary};
+use crate::store::Snapshot;
+use serde::Serialize;
+
+/// A point in a branch's recorded history where its check results changed
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineEntry {
+    /// RFC 3339 time of the first snapshot showing this state
+    pub fetched_at: String,
+    pub head_sha: Option<String>,
+    pub pr: Option<u64>,
+    pub summary: CheckSummary,
+    pub failed: Vec<String>,
+}
+
+/// Collapse recorded snapshots into the changes seen on one branch, oldest first
+pub fn branch_timeline(snapshots: &[Snapshot], branch: &str) -> Vec<TimelineEntry> {
+    let mut entries: Vec<TimelineEntry> = Vec::new();
+
+    for snapshot in snapshots {
+        let Some(b) = snapshot.branches.iter().find(|b| b.branch == branch) else {
+            continue;
+        };
+
+        let summary = github::summarize_checks(&b.checks);
+        let failed: Vec<String> = b
+            .checks
+            .iter()
+            .filter(|c| c.status == CheckStatus::Failed)
+            .map(|c| c.name.clone())
+            .collect();
+
+        let unchanged = entries.last().is_some_and(|last| {
+            last.head_sha == b.head_sha
+                && last.summary.text() == summary.text()
+                && last.summary.overall == summary.overall
+                && last.failed == failed
+        });
+        if unchanged {
+            continue;
+        }
+
+        entries.push(TimelineEntry {
+            fetched_at: snapshot.fetched_at.clone(),
+            head_sha: b.head_sha.clone(),
+            pr: b.pr,
+            summary,
+            failed,
+        });
+    }
+
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn snapshot(at: &str, sha: &str, statuses: &[(&str, CheckStatus)]) -> Snapshot {
+        testing::snapshot(at, sha, testing::checks(statuses))
+    }
+
+    #[test]
+    fn test_timeline_keeps_only_changes() {
+        let snapshots = vec![
+            snapshot("t1", "aaa", &[("test", CheckStatus::Running)]),
+            snapshot("t2", "aaa", &[("test", CheckStatus::Running)]),
+            snapshot("t3", "aaa", &[("test", CheckStatus::Failed)]),
+            snapshot("t4", "bbb", &[("test", CheckStatus::Running)]),
+            snapshot("t5", "bbb", &[("test", CheckStatus::Passed)]),
+        ];
+
+        let timeline = branch_timeline(&snapshots, "feature");
+        let times: Vec<_> = timeline.iter().map(|e| e.fetched_at.as_str()).collect();
+        assert_eq!(times, ["t1", "t3", "t4", "t5"]);
+        assert_eq!(timeline[1].failed, ["test"]);
+        assert!(branch_timeline(&snapshots, "other").is_empty());
+    }
+}
@@ -0,0 +1,131 @@
+use crate::events::Event;
+use serde::Deserialize;
+use std::process::Stdio;
+use tokio::process::Command;
+
+/// Shell commands to run when checks finish
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Hooks {
+    /// Run for each check that fails
+    pub on_fail: Option<String>,
+    /// Run when all of a branch's checks pass
+    pub on_pass: Option<String>,
+    /// Run when no check in the stack is running or queued any more
+    pub on_complete: Option<String>,
+}
+
+impl Hooks {
+    /// Command-line hooks take precedence over the config file
+    pub fn or(self, fallback: Hooks) -> Hooks {
+        Hooks {
+            on_fail: self.on_fail.or(fallback.on_fail),
+            on_pass: self.on_pass.or(fallback.on_pass),
+            on_complete: self.on_complete.or(fallback.on_complete),
+        }
+    }
+
+    /// The hook command and `STACK_STATUS_*` environment to run for an event, if any
+    pub fn invocation(&self, event: &Event) -> Option<(&str, Vec<(&'static str, String)>)> {
+        let pr = |pr: &Option<u64>| pr.map(|n| n.to_string()).unwrap_or_default();
+
+        match event {
+            Event::CheckFailed(check) => Some((
+                self.on_fail.as_deref()?,
+                vec![
+                    ("STACK_STATUS_BRANCH", check.branch.clone()),
+                    ("STACK_STATUS_PR", pr(&check.pr)),
+                    ("STACK_STATUS_CHECK", check.check.clone()),
+                    ("STACK_STATUS_STATUS", "failed".to_string()),
+                    ("STACK_STATUS_URL", check.url.clone().unwrap_or_default()),
+                ],
+            )),
+            Event::ChecksPassed { branch, pr: n, .. } => Some((
+                self.on_pass.as_deref()?,
+                vec![
+                    ("STACK_STATUS_BRANCH", branch.clone()),
+                    ("STACK_STATUS_PR", pr(n)),
+                    ("STACK_STATUS_STATUS", "passed".to_string()),
+                ],
+            )),
+            Event::StackSettled { failing } => Some((
+                self.on_complete.as_deref()?,
+                vec![
+                    (
+                        "STACK_STATUS_STATUS",
+                        if failing.is_empty() { "passed" } else { "failed" }.to_string(),
+                    ),
+                    ("STACK_STATUS_FAILING", failing.join(",")),
+                ],
+            )),
+            _ => None,
+        }
+    }
+
+    /// Start the matching hooks in the background; output is discarded so the watch display stays intact
+    pub fn run(&self, events: &[Event]) {
+        for (cmd, env) in events.iter().filter_map(|e| self.invocation(e)) {
+            let mut command = shell(cmd);
+            command
+                .envs(env)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null());
+            command.spawn().ok();
+        }
+    }
+}
+
+#[cfg(unix)]
+fn shell(cmd: &str) -> Command {
+    let mut command = Command::new("sh");
+    command.args(["-c", cmd]);
+    command
+}
+
+#[cfg(windows)]
+fn shell(cmd: &str) -> Command {
+    let mut command = Command::new("cmd");
+    command.args(["/C", cmd]);
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::CheckEvent;
+
+    #[test]
+    fn test_cli_hooks_override_config() {
+        let cli = Hooks {
+            on_fail: Some("open \"$STACK_STATUS_URL\"".to_string()),
+            ..Default::default()
+        };
+        let config = Hooks {
+            on_fail: Some("echo config".to_string()),
+            on_complete: Some("say done".to_string()),
+            ..Default::default()
+        };
+        let hooks = cli.or(config);
+
+        let failed = Event::CheckFailed(CheckEvent {
+            branch: "feature".to_string(),
+            pr: Some(7),
+            check: "test".to_string(),
+            url: Some("https://ci/1".to_string()),
+        });
+        let (cmd, env) = hooks.invocation(&failed).unwrap();
+        assert_eq!(cmd, "open \"$STACK_STATUS_URL\"");
+        assert!(env.contains(&("STACK_STATUS_PR", "7".to_string())));
+        assert!(env.contains(&("STACK_STATUS_URL", "https://ci/1".to_string())));
+
+        let passed = Event::ChecksPassed {
+            branch: "feature".to_string(),
+            pr: Some(7),
+            total: 3,
+        };
+        assert!(hooks.invocation(&passed).is_none());
+        assert!(hooks
+            .invocation(&Event::StackSettled { failing: Vec::new() })
+            .is_some_and(|(cmd, _)| cmd == "say done"));
+    }
+}
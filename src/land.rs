@@ -0,0 +1,354 @@
+use crate::github::{self, MergeMethod, PrState, ReviewDecision, ReviewStatus};
+use crate::graphite;
+use crate::ready::{self, Blocker, ReadyReport};
+use crate::StackStatus;
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// What landing should do next, given the current readiness report
+#[derive(Debug, PartialEq)]
+pub enum Step {
+    /// Every PR in the stack has landed
+    Done,
+    /// The bottom PR is ready to merge
+    Merge { branch: String, pr: u64 },
+    /// The bottom PR only waits on checks that are still running
+    Wait { branch: String },
+    /// The bottom PR can't land without someone stepping in
+    Stop { branch: String, blockers: Vec<Blocker> },
+}
+
+/// Pick the next step for the bottom-most unmerged PR
+pub fn next_step(report: &ReadyReport) -> Step {
+    let Some(bottom) = report.branches.iter().find(|b| !b.landed) else {
+        return Step::Done;
+    };
+
+    if bottom.landable {
+        if let Some(pr) = bottom.pr {
+            return Step::Merge {
+                branch: bottom.branch.clone(),
+                pr,
+            };
+        }
+    }
+
+    let only_pending = !bottom.blockers.is_empty()
+        && bottom
+            .blockers
+            .iter()
+            .all(|b| matches!(b, Blocker::ChecksPending { .. }));
+
+    if only_pending {
+        Step::Wait {
+            branch: bottom.branch.clone(),
+        }
+    } else {
+        Step::Stop {
+            branch: bottom.branch.clone(),
+            blockers: bottom.blockers.clone(),
+        }
+    }
+}
+
+/// Options for landing the stack
+pub struct LandOptions<'a> {
+    pub branch: Option<&'a str>,
+    pub method: MergeMethod,
+    pub dry_run: bool,
+    pub confirmed: bool,
+    pub interval: u64,
+    pub has_gt: bool,
+}
+
+/// Watch the stack and land it bottom-up, stopping at the first PR that can't land
+pub async fn run(opts: LandOptions<'_>) -> Result<()> {
+    if !opts.dry_run && !opts.confirmed {
+        bail!("landing merges PRs; pass --yes to confirm or --dry-run to preview");
+    }
+
+    let mut status = fetch_status(&opts, opts.branch).await?;
+    if opts.dry_run {
+        print_plan(&evaluate(&status).await, opts.method);
+        return Ok(());
+    }
+    if opts.has_gt && !status.branches.iter().any(|b| b.is_current) {
+        bail!(
+            "{} isn't in the checked-out stack; gt sync, restack and submit act on the \
+             current stack, so check it out first",
+            opts.branch.unwrap_or_default()
+        );
+    }
+
+    // Land exactly the branches stacked now. Once merged branches are deleted,
+    // looking the stack up again could resolve to trunk or another stack.
+    let stack: Vec<String> = status
+        .branches
+        .iter()
+        .filter(|b| !b.is_trunk)
+        .map(|b| b.branch.clone())
+        .collect();
+    let mut landed: Vec<String> = status
+        .branches
+        .iter()
+        .filter(|b| b.pr_state == Some(PrState::Merged))
+        .map(|b| b.branch.clone())
+        .collect();
+    // The top branch lands last, so it exists for as long as there's work left
+    let Some(top) = stack.first().cloned() else {
+        bail!("no stack to land");
+    };
+    // Head SHAs pushed by our last submit, which CI has to pick up before evaluating
+    let mut pushed: HashMap<String, String> = HashMap::new();
+
+    loop {
+        // After a submit, GitHub needs to see the new heads and CI to report on them
+        let step = match awaiting_ci(&status, &pushed) {
+            Some(branch) => Step::Wait { branch },
+            None => next_step(&evaluate(&status).await),
+        };
+        match step {
+            Step::Done => {
+                println!("✓ Stack fully landed");
+                return Ok(());
+            }
+            Step::Merge { branch, pr } => {
+                println!("Merging #{} ({}) with {}…", pr, branch, opts.method.as_str());
+                github::merge_pr(pr, opts.method).await?;
+                landed.push(branch);
+
+                if opts.has_gt {
+                    println!("Syncing, restacking and resubmitting…");
+                    graphite::sync().await?;
+                    graphite::restack().await?;
+                    if landed.len() < stack.len() {
+                        graphite::submit().await?;
+                        pushed = local_heads(&stack, &landed).await;
+                    }
+                }
+            }
+            Step::Wait { branch } => {
+                println!(
+                    "Waiting on checks for {} (next poll in {}s)",
+                    branch, opts.interval
+                );
+                tokio::time::sleep(Duration::from_secs(opts.interval)).await;
+            }
+            Step::Stop { branch, blockers } => {
+                println!("✗ Stopping at {}:", branch);
+                for blocker in blockers {
+                    println!("    - {}", blocker.describe());
+                }
+                bail!("{} cannot land", branch);
+            }
+        }
+
+        if stack.iter().all(|b| landed.contains(b)) {
+            println!("✓ Stack fully landed");
+            return Ok(());
+        }
+
+        status = fetch_status(&opts, Some(&top)).await?;
+        status.branches.retain(|b| stack.contains(&b.branch));
+        if let Some(missing) = stack
+            .iter()
+            .find(|name| !landed.contains(name) && !status.branches.iter().any(|b| &b.branch == *name))
+        {
+            bail!("{} is no longer in the stack; stopping", missing);
+        }
+    }
+}
+
+async fn fetch_status(opts: &LandOptions<'_>, branch: Option<&str>) -> Result<StackStatus> {
+    let mut status = crate::fetch_stack_status(branch, opts.has_gt, true).await?;
+    require_approval(&mut status);
+    Ok(status)
+}
+
+async fn evaluate(status: &StackStatus) -> ReadyReport {
+    let required = crate::fetch_required_checks(status).await;
+    ready::evaluate(status, &required)
+}
+
+/// Landing merges for real, so a PR nobody approved counts as unreviewed
+///
+/// GitHub leaves the review decision empty when the repo doesn't require
+/// reviews, so fall back to the reviews themselves.
+fn require_approval(status: &mut StackStatus) {
+    for branch in status.branches.iter_mut().filter(|b| b.pr.is_some()) {
+        let review = branch.review.get_or_insert_with(|| ReviewStatus {
+            decision: None,
+            approvals: Vec::new(),
+            changes_requested: Vec::new(),
+            pending: Vec::new(),
+        });
+        if review.decision.is_none() {
+            if !review.changes_requested.is_empty() {
+                review.decision = Some(ReviewDecision::ChangesRequested);
+            } else if review.approvals.is_empty() {
+                review.decision = Some(ReviewDecision::ReviewRequired);
+            }
+        }
+    }
+}
+
+/// Local tips of the branches still to land, as just pushed by `gt submit`
+async fn local_heads(stack: &[String], landed: &[String]) -> HashMap<String, String> {
+    let mut heads = HashMap::new();
+    for branch in stack.iter().filter(|b| !landed.contains(b)) {
+        if let Some(sha) = graphite::branch_sha(branch).await {
+            heads.insert(branch.clone(), sha);
+        }
+    }
+    heads
+}
+
+/// The bottom unmerged branch, if GitHub hasn't seen its pushed head or CI hasn't reported on it
+fn awaiting_ci(status: &StackStatus, pushed: &HashMap<String, String>) -> Option<String> {
+    let bottom = status
+        .branches
+        .iter()
+        .rev()
+        .find(|b| !b.is_trunk && b.pr_state != Some(PrState::Merged))?;
+    let sha = pushed.get(&bottom.branch)?;
+
+    let reported = bottom.head_sha.as_ref() == Some(sha)
+        && bottom.checks.as_ref().is_some_and(|c| !c.is_empty());
+    (!reported).then(|| bottom.branch.clone())
+}
+
+/// Describe what a real run would merge, without touching anything
+fn print_plan(report: &ReadyReport, method: MergeMethod) {
+    println!("Dry run: no PRs will be merged");
+    println!();
+
+    let mut merges = 0;
+    for branch in report.branches.iter().filter(|b| !b.landed) {
+        match (branch.landable, branch.pr) {
+            (true, Some(pr)) => {
+                println!("  would merge #{} ({}) with {}", pr, branch.branch, method.as_str());
+                merges += 1;
+            }
+            _ => {
+                println!("  then stop at {}:", branch.branch);
+                for blocker in &branch.blockers {
+                    println!("    - {}", blocker.describe());
+                }
+                break;
+            }
+        }
+    }
+
+    if merges == 0 && report.first_blocked.is_none() {
+        println!("  nothing to land");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::CheckStatus;
+    use crate::ready::BranchReadiness;
+    use crate::testing;
+    use crate::BranchStatus;
+    use std::collections::HashMap;
+
+    fn readiness(branch: &str, landed: bool, blockers: Vec<Blocker>) -> BranchReadiness {
+        BranchReadiness {
+            branch: branch.to_string(),
+            pr: Some(1),
+            landed,
+            landable: !landed && blockers.is_empty(),
+            blockers,
+        }
+    }
+
+    fn report(branches: Vec<BranchReadiness>) -> ReadyReport {
+        ReadyReport {
+            landable: branches.iter().filter(|b| b.landable).count(),
+            first_blocked: None,
+            branches,
+        }
+    }
+
+    #[test]
+    fn test_next_step() {
+        let merged = readiness("a", true, Vec::new());
+        assert_eq!(next_step(&report(vec![merged])), Step::Done);
+
+        let ready = readiness("b", false, Vec::new());
+        assert_eq!(
+            next_step(&report(vec![ready])),
+            Step::Merge {
+                branch: "b".to_string(),
+                pr: 1
+            }
+        );
+
+        let pending = readiness(
+            "c",
+            false,
+            vec![Blocker::ChecksPending {
+                checks: vec!["test".to_string()],
+            }],
+        );
+        assert_eq!(
+            next_step(&report(vec![pending])),
+            Step::Wait {
+                branch: "c".to_string()
+            }
+        );
+
+        let failing = readiness("d", false, vec![Blocker::ReviewRequired]);
+        assert!(matches!(next_step(&report(vec![failing])), Step::Stop { .. }));
+    }
+
+    fn open_pr(approvals: &[&str]) -> StackStatus {
+        let mut status = StackStatus::new();
+        status.branches = vec![BranchStatus {
+            pr: Some(1),
+            pr_state: Some(PrState::Open),
+            head_sha: Some("bbb".to_string()),
+            review: Some(ReviewStatus {
+                decision: None,
+                approvals: approvals.iter().map(|a| a.to_string()).collect(),
+                changes_requested: Vec::new(),
+                pending: Vec::new(),
+            }),
+            ..testing::branch("a", testing::checks(&[("test", CheckStatus::Passed)]))
+        }];
+        status
+    }
+
+    #[test]
+    fn test_approvals_decide_without_review_decision() {
+        // Repos without required reviews report no decision, approved or not
+        let mut unreviewed = open_pr(&[]);
+        require_approval(&mut unreviewed);
+        let report = ready::evaluate(&unreviewed, &HashMap::new());
+        assert_eq!(report.branches[0].blockers, vec![Blocker::ReviewRequired]);
+
+        let mut approved = open_pr(&["alice"]);
+        require_approval(&mut approved);
+        assert!(ready::evaluate(&approved, &HashMap::new()).branches[0].landable);
+    }
+
+    #[test]
+    fn test_awaiting_ci_after_submit() {
+        let mut status = open_pr(&["alice"]);
+        let pushed = HashMap::from([("a".to_string(), "ccc".to_string())]);
+
+        // GitHub still shows the old head
+        assert_eq!(awaiting_ci(&status, &pushed).as_deref(), Some("a"));
+
+        // New head seen, but no checks reported on it yet
+        status.branches[0].head_sha = Some("ccc".to_string());
+        status.branches[0].checks = Some(Vec::new());
+        assert_eq!(awaiting_ci(&status, &pushed).as_deref(), Some("a"));
+
+        status.branches[0].checks = Some(testing::checks(&[("test", CheckStatus::Running)]));
+        assert_eq!(awaiting_ci(&status, &pushed), None);
+        assert_eq!(awaiting_ci(&status, &HashMap::new()), None);
+    }
+}
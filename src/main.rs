@@ -1,10 +1,29 @@
+mod config;
+mod daemon;
 mod display;
+mod eta;
+mod events;
+mod flaky;
 mod github;
 mod graphite;
+mod history;
+mod hooks;
+mod land;
+mod markdown;
 mod mcp;
+mod notify;
+mod prompt;
+mod ready;
+mod stats;
+mod store;
+mod template;
+#[cfg(test)]
+mod testing;
+mod webhook;
 
 use anyhow::Result;
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use std::collections::HashMap;
 use std::time::Duration;
 use tokio::time::interval;
 
@@ -18,24 +37,112 @@ struct Args {
     watch: bool,
 
     /// Refresh interval in seconds (default: 10)
-    #[arg(short, long, default_value = "10")]
+    #[arg(short, long, default_value = "10", global = true)]
     interval: u64,
 
-    /// Show specific branch's stack (default: current branch)
-    #[arg(short, long)]
+    /// Show the stack containing this branch (default: current branch)
+    #[arg(short, long, global = true)]
     branch: Option<String>,
 
     /// Output as JSON
-    #[arg(long)]
+    #[arg(long, global = true)]
     json: bool,
 
+    /// Output format for a one-shot run
+    #[arg(long, value_enum, default_value = "text", conflicts_with_all = ["template", "watch"])]
+    format: display::OutputFormat,
+
+    /// Render a one-shot run through a template (inline, or @FILE to read one)
+    #[arg(long, value_name = "TEMPLATE", conflicts_with_all = ["json", "watch"])]
+    template: Option<String>,
+
+    /// Never call gh: use a running daemon or the cached status of the current branch
+    #[arg(long)]
+    cached: bool,
+
     /// Run as MCP server (stdio transport)
     #[arg(long)]
     mcp: bool,
 
+    /// Run as MCP server over streamable HTTP/SSE on the given address (e.g. 127.0.0.1:8765)
+    #[arg(long, value_name = "ADDR", conflicts_with = "mcp")]
+    mcp_http: Option<std::net::SocketAddr>,
+
+    /// Watch mode: send a notification when a branch's checks finish or the stack settles
+    #[arg(long)]
+    notify: bool,
+
+    /// Watch mode: shell command to run for each check that fails
+    #[arg(long, value_name = "CMD")]
+    on_fail: Option<String>,
+
+    /// Watch mode: shell command to run when all of a branch's checks pass
+    #[arg(long, value_name = "CMD")]
+    on_pass: Option<String>,
+
+    /// Watch mode: shell command to run when the whole stack settles
+    #[arg(long, value_name = "CMD")]
+    on_complete: Option<String>,
+
+    /// Watch mode: POST to this chat webhook when the stack's aggregate status changes
+    #[arg(long, value_name = "URL")]
+    webhook: Option<String>,
+
+    /// Payload format for --webhook, overriding the config file [default: slack]
+    #[arg(long, value_enum)]
+    webhook_kind: Option<webhook::WebhookKind>,
+
     /// Show detailed check information
     #[arg(short, long)]
     details: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Report how far up the stack can land right now and what blocks the rest
+    Ready,
+
+    /// Watch the stack and merge PRs bottom-up once they're green and approved
+    Land {
+        /// Show what would be merged without merging anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Confirm that PRs should really be merged
+        #[arg(long)]
+        yes: bool,
+
+        /// Merge method passed to gh pr merge
+        #[arg(long, value_enum, default_value = "squash")]
+        method: github::MergeMethod,
+    },
+
+    /// Show how a branch's checks evolved across fetches and pushes
+    History {
+        /// Branch to show (default: --branch, then the current branch)
+        #[arg(value_name = "BRANCH")]
+        target: Option<String>,
+    },
+
+    /// Print a compact status for the current branch from the cache, for shell prompts
+    Prompt,
+
+    /// Poll the current repo's stack in the background and serve it over a Unix socket
+    Daemon,
+
+    /// Aggregate recorded check history into pass rates, durations and CI minutes
+    Stats {
+        /// Only include PRs by this GitHub login
+        #[arg(long)]
+        author: Option<String>,
+
+        /// Only include runs seen within this window (e.g. 24h, 7d, 2w) or since a date (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
+    },
 }
 
 #[tokio::main]
@@ -47,6 +154,33 @@ async fn main() -> Result<()> {
         return mcp::run_server().await;
     }
 
+    if let Some(addr) = args.mcp_http {
+        return mcp::run_http_server(addr).await;
+    }
+
+    // Cached one-shot output (status lines, prompts) must stay fast, so skip the tool checks
+    if args.cached && args.command.is_none() && !args.watch {
+        return run_once(&args, false, false).await;
+    }
+
+    // Commands over recorded history don't need gh
+    match args.command {
+        Some(Command::Prompt) => {
+            prompt::run().await;
+            return Ok(());
+        }
+        Some(Command::History { ref target }) => {
+            return run_history(&args, target.as_deref()).await;
+        }
+        Some(Command::Stats {
+            ref author,
+            ref since,
+        }) => {
+            return run_stats(&args, author.as_deref(), since.as_deref()).await;
+        }
+        _ => {}
+    }
+
     // Check prerequisites
     let has_gt = graphite::is_installed().await;
     let has_gh = github::is_installed().await;
@@ -61,6 +195,30 @@ async fn main() -> Result<()> {
         eprintln!("         Showing current branch only (no stack hierarchy).");
     }
 
+    match args.command {
+        Some(Command::Ready) => return run_ready(&args, has_gt, has_gh).await,
+        Some(Command::Land {
+            dry_run,
+            yes,
+            method,
+        }) => {
+            if !has_gh {
+                anyhow::bail!("landing requires the GitHub CLI (gh)");
+            }
+            return land::run(land::LandOptions {
+                branch: args.branch.as_deref(),
+                method,
+                dry_run,
+                confirmed: yes,
+                interval: args.interval,
+                has_gt,
+            })
+            .await;
+        }
+        Some(Command::Daemon) => return daemon::run(args.interval, has_gt, has_gh).await,
+        Some(Command::History { .. } | Command::Stats { .. } | Command::Prompt) | None => {}
+    }
+
     // Single run or watch mode
     if args.watch {
         run_watch_mode(&args, has_gt, has_gh).await
@@ -70,12 +228,146 @@ async fn main() -> Result<()> {
 }
 
 async fn run_once(args: &Args, has_gt: bool, has_gh: bool) -> Result<()> {
-    let status = fetch_status(args, has_gt, has_gh).await?;
+    // Parse the template first so mistakes show up before any fetching
+    let template = match args.template {
+        Some(ref source) => Some(load_template(source)?),
+        None => None,
+    };
+
+    let status = if args.cached {
+        match cached_status(args).await {
+            Some(status) => status,
+            None => return Ok(()),
+        }
+    } else {
+        fetch_status(args, has_gt, has_gh).await?
+    };
 
     if args.json {
         println!("{}", serde_json::to_string_pretty(&status)?);
+        return Ok(());
+    }
+
+    if let Some(template) = template {
+        let out = template.render(&serde_json::to_value(&status)?);
+        print!("{}", out);
+        if !out.ends_with('\n') {
+            println!();
+        }
+        return Ok(());
+    }
+
+    match args.format {
+        display::OutputFormat::Text => display::render(&status, args.details),
+        display::OutputFormat::Tmux => println!("{}", display::tmux_line(&status)),
+        display::OutputFormat::Markdown => print!("{}", markdown::render(&status)),
+    }
+
+    Ok(())
+}
+
+/// Parse `--template`, reading it from a file when given as `@path`
+///
+/// A leading `~/` is expanded, since the shell won't do it after the `@`.
+fn load_template(source: &str) -> Result<template::Template> {
+    use anyhow::Context;
+
+    match source.strip_prefix('@') {
+        Some(path) => {
+            let file = match (path.strip_prefix("~/"), dirs::home_dir()) {
+                (Some(rest), Some(home)) => home.join(rest),
+                _ => std::path::PathBuf::from(path),
+            };
+            let contents = std::fs::read_to_string(&file)
+                .with_context(|| format!("reading template {}", path))?;
+            template::Template::parse(&contents).with_context(|| format!("in template {}", path))
+        }
+        None => template::Template::parse(source),
+    }
+}
+
+/// Status without calling gh: the daemon's copy, or the current branch from the cache
+async fn cached_status(args: &Args) -> Option<StackStatus> {
+    if let Some(status) = daemon::query_cached(args.branch.as_deref()).await {
+        return Some(status);
+    }
+
+    let (toplevel, current) = graphite::get_checkout().await?;
+    let branch = args.branch.clone().unwrap_or(current);
+    let cached = store::BranchCache::for_checkout(&toplevel)
+        .get(&branch)
+        .filter(|c| !c.is_stale(chrono::Utc::now()))?;
+
+    let mut status = StackStatus::new();
+    status.fetched_at = cached.fetched_at;
+    status.branches.push(BranchStatus {
+        is_current: args.branch.is_none(),
+        is_focus: true,
+        pr: cached.pr,
+        pr_state: cached.pr_state,
+        summary: cached.summary,
+        branch,
+        ..Default::default()
+    });
+    Some(status)
+}
+
+async fn run_ready(args: &Args, has_gt: bool, has_gh: bool) -> Result<()> {
+    // Merge decisions shouldn't rest on a daemon's cached copy
+    let status = fetch_stack_status(args.branch.as_deref(), has_gt, has_gh).await?;
+    let required = if has_gh {
+        fetch_required_checks(&status).await
+    } else {
+        HashMap::new()
+    };
+
+    let report = ready::evaluate(&status, &required);
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        display::render_ready_report(&report);
+    }
+
+    Ok(())
+}
+
+async fn run_history(args: &Args, target: Option<&str>) -> Result<()> {
+    let branch = match target.or(args.branch.as_deref()) {
+        Some(branch) => branch.to_string(),
+        None => graphite::get_current_branch().await?,
+    };
+
+    let repo = graphite::get_repo_id().await.unwrap_or_default();
+    let snapshots = store::SnapshotStore::for_repo(&repo).load();
+    let timeline = history::branch_timeline(&snapshots, &branch);
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&timeline)?);
     } else {
-        display::render(&status, args.details);
+        display::render_history(&branch, &timeline);
+    }
+
+    Ok(())
+}
+
+async fn run_stats(args: &Args, author: Option<&str>, since: Option<&str>) -> Result<()> {
+    let filter = stats::StatsFilter {
+        branch: args.branch.as_deref(),
+        author,
+        since: since
+            .map(|s| stats::parse_since(s, chrono::Utc::now()))
+            .transpose()?,
+    };
+
+    let repo = graphite::get_repo_id().await.unwrap_or_default();
+    let snapshots = store::SnapshotStore::for_repo(&repo).load();
+    let report = stats::compute(&snapshots, &filter);
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        display::render_stats(&report);
     }
 
     Ok(())
@@ -96,6 +388,30 @@ async fn run_watch_mode(args: &Args, has_gt: bool, has_gh: bool) -> Result<()> {
     // Cached status
     let mut status = fetch_status(args, has_gt, has_gh).await?;
 
+    let config = config::Config::load()?;
+    let hooks = hooks::Hooks {
+        on_fail: args.on_fail.clone(),
+        on_pass: args.on_pass.clone(),
+        on_complete: args.on_complete.clone(),
+    }
+    .or(config.hooks);
+
+    // Flags override the `[webhook]` config field by field
+    let mut webhook_config = config.webhook;
+    if let Some(ref url) = args.webhook {
+        webhook_config.url = Some(url.clone());
+    }
+    if let Some(kind) = args.webhook_kind {
+        webhook_config.kind = kind;
+    }
+    let webhook = webhook::Webhook::from_config(webhook_config);
+
+    let notifier = if args.notify {
+        Some(notify::Notifier::detect().await)
+    } else {
+        None
+    };
+
     // Set up terminal
     display::setup_terminal()?;
 
@@ -112,7 +428,29 @@ async fn run_watch_mode(args: &Args, has_gt: bool, has_gh: bool) -> Result<()> {
         // Refresh data periodically
         if ticks_since_refresh >= ticks_per_refresh {
             ticks_since_refresh = 0;
-            status = fetch_status(args, has_gt, has_gh).await?;
+            let next = fetch_status(args, has_gt, has_gh).await?;
+
+            let changes = events::diff(&status, &next);
+            if let Some(notifier) = notifier {
+                for note in changes.iter().filter_map(notify::for_event) {
+                    notifier.send(&note).await;
+                }
+            }
+            hooks.run(&changes);
+
+            let changed = changes
+                .iter()
+                .any(|e| matches!(e, events::Event::StackStatusChanged { .. }));
+            if let Some(webhook) = webhook.clone().filter(|_| changed) {
+                // Post in the background so a slow endpoint doesn't stall the display
+                let next = next.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = webhook.send(&next).await {
+                        eprintln!("stack-status: webhook failed: {:#}", e);
+                    }
+                });
+            }
+            status = next;
         }
 
         // Clear screen and render with current animation frame
@@ -122,6 +460,9 @@ async fn run_watch_mode(args: &Args, has_gt: bool, has_gh: bool) -> Result<()> {
             println!("{}", serde_json::to_string_pretty(&status)?);
         } else {
             display::render_with_frame(&status, args.details, frame);
+            if status.all_complete() {
+                display::render_complete_message();
+            }
             display::render_help_bar();
         }
 
@@ -142,42 +483,63 @@ async fn run_watch_mode(args: &Args, has_gt: bool, has_gh: bool) -> Result<()> {
     Ok(())
 }
 
-async fn fetch_status(_args: &Args, has_gt: bool, has_gh: bool) -> Result<StackStatus> {
+/// Fetch status for display, from a running daemon if there is one
+async fn fetch_status(args: &Args, has_gt: bool, has_gh: bool) -> Result<StackStatus> {
+    if let Some(status) = daemon::query(args.branch.as_deref()).await {
+        return Ok(status);
+    }
+    fetch_stack_status(args.branch.as_deref(), has_gt, has_gh).await
+}
+
+/// Fetch the stack containing `branch` (default: current branch) with PR and
+/// check status for each branch. Shared by the CLI and the MCP server.
+pub async fn fetch_stack_status(
+    branch: Option<&str>,
+    has_gt: bool,
+    has_gh: bool,
+) -> Result<StackStatus> {
     let mut status = StackStatus::new();
 
-    // Get stack from Graphite or fall back to current branch
-    let branches = if has_gt {
-        graphite::get_stack().await?
-    } else {
-        // Fall back to current branch only
-        let current = graphite::get_current_branch().await?;
-        vec![BranchInfo {
-            name: current,
-            is_current: true,
-            is_trunk: false,
-        }]
-    };
+    let current = graphite::get_current_branch().await?;
+    let focus = branch.unwrap_or(&current).to_string();
+    let branches = resolve_stack(&focus, &current, has_gt, has_gh).await?;
 
     // Get PR and check status for each branch
     for branch in branches {
+        let is_focus = branch.name == focus;
+
         if branch.is_trunk {
             status.branches.push(BranchStatus {
                 branch: branch.name,
                 is_current: branch.is_current,
+                is_focus,
                 is_trunk: true,
                 pr: None,
+                pr_url: None,
+                pr_state: None,
+                head_sha: None,
+                author: None,
+                local_sync: None,
+                review: None,
+                merge: None,
+                unresolved_threads: None,
+                merge_queue: None,
+                deployments: None,
                 checks: None,
                 summary: None,
+                eta: None,
             });
             continue;
         }
 
         let (pr, checks) = if has_gh {
-            let pr = github::get_pr_for_branch(&branch.name).await;
-            let checks = if pr.is_some() {
-                Some(github::get_checks(&branch.name).await?)
-            } else {
-                None
+            let pr = github::get_pr(&branch.name).await;
+            // Merged and closed PRs are settled; skip polling their checks
+            let checks = match pr {
+                Some(ref p) if !p.state.is_settled() => {
+                    Some(github::get_checks(&branch.name).await?)
+                }
+                _ => None,
             };
             (pr, checks)
         } else {
@@ -186,24 +548,181 @@ async fn fetch_status(_args: &Args, has_gt: bool, has_gh: bool) -> Result<StackS
 
         let summary = checks.as_ref().map(|c| github::summarize_checks(c));
 
+        let local_sync = match pr {
+            Some(ref p) if !p.state.is_settled() => match p.head_sha {
+                Some(ref sha) => graphite::compare_with_pr_head(&branch.name, sha).await,
+                None => None,
+            },
+            _ => None,
+        };
+
+        let activity = match pr {
+            Some(ref p) if !p.state.is_settled() => github::get_pr_activity(p.number).await,
+            _ => None,
+        };
+
         status.branches.push(BranchStatus {
             branch: branch.name,
             is_current: branch.is_current,
+            is_focus,
             is_trunk: false,
-            pr,
+            pr: pr.as_ref().map(|p| p.number),
+            pr_url: pr.as_ref().and_then(|p| p.url.clone()),
+            pr_state: pr.as_ref().map(|p| p.state),
+            head_sha: pr.as_ref().and_then(|p| p.head_sha.clone()),
+            author: pr.as_ref().and_then(|p| p.author.clone()),
+            local_sync,
+            review: pr.as_ref().map(|p| p.review.clone()),
+            merge: pr.map(|p| p.merge),
+            unresolved_threads: activity.as_ref().map(|a| a.unresolved_threads),
+            merge_queue: activity.as_ref().and_then(|a| a.merge_queue.clone()),
+            deployments: activity.map(|a| a.deployments),
             checks,
             summary,
+            eta: None,
         });
     }
 
-    status.timestamp = chrono::Local::now().format("%H:%M:%S").to_string();
+    // Learn check durations and estimate how long running checks have left
+    let repo = graphite::get_repo_id().await.unwrap_or_default();
+    let mut durations = store::DurationHistory::load(&repo);
+    let all_checks: Vec<_> = status
+        .branches
+        .iter()
+        .filter_map(|b| b.checks.as_ref())
+        .flatten()
+        .cloned()
+        .collect();
+    if durations.record(&all_checks) {
+        durations.save().ok();
+    }
+    eta::apply(&mut status, &durations, chrono::Utc::now());
+
+    let now = chrono::Local::now();
+    status.timestamp = now.format("%H:%M:%S").to_string();
+    status.fetched_at = now.to_rfc3339();
+
+    // Record before scoring so a retry that just passed counts as a flip
+    let snapshots = store::SnapshotStore::for_repo(&repo);
+    snapshots.record(&status).ok();
+    flaky::apply(&mut status, &snapshots.recent());
+
+    if let Some(toplevel) = graphite::get_toplevel().await {
+        store::BranchCache::for_checkout(&toplevel).update(&status).ok();
+    }
+
     Ok(status)
 }
 
-#[derive(Debug, serde::Serialize)]
+/// Look up required check names for every open PR in the stack
+///
+/// Required checks come from branch protection on each PR's base. Branches
+/// whose required checks can't be determined are left out.
+pub async fn fetch_required_checks(status: &StackStatus) -> HashMap<String, Vec<String>> {
+    let mut required = HashMap::new();
+    for branch in status.branches.iter().filter(|b| !b.is_trunk) {
+        let open = !branch.pr_state.is_some_and(|s| s.is_settled());
+        let Some(pr) = branch.pr.filter(|_| open) else {
+            continue;
+        };
+        if let Some(names) = github::get_required_check_names(pr).await {
+            required.insert(branch.branch.clone(), names);
+        }
+    }
+    required
+}
+
+/// Work out which branches make up the stack containing `branch`
+///
+/// The current branch comes straight from `gt log short`. Other branches use
+/// Graphite's branch metadata, then PR base chains, and finally fall back to
+/// showing the branch on its own if it exists locally.
+async fn resolve_stack(
+    branch: &str,
+    current: &str,
+    has_gt: bool,
+    has_gh: bool,
+) -> Result<Vec<BranchInfo>> {
+    if has_gt && branch == current {
+        return graphite::get_stack().await;
+    }
+
+    let stack = graphite::get_stack_for_branch(branch, current).await?;
+    if !stack.is_empty() {
+        return Ok(stack);
+    }
+
+    if has_gh {
+        let stack = resolve_stack_from_prs(branch, current).await;
+        if stack.len() > 1 {
+            return Ok(stack);
+        }
+    }
+
+    // Without metadata or a PR chain the branch must at least exist, so a typo isn't
+    // shown as a one-branch stack
+    if branch != current && graphite::branch_sha(branch).await.is_none() {
+        anyhow::bail!("no local branch or PR found for '{}'", branch);
+    }
+
+    Ok(vec![BranchInfo {
+        name: branch.to_string(),
+        is_current: branch == current,
+        is_trunk: false,
+    }])
+}
+
+/// Infer a stack by following PR base branches down to trunk and PRs based
+/// on each branch up to the top of the stack
+async fn resolve_stack_from_prs(branch: &str, current: &str) -> Vec<BranchInfo> {
+    let mut seen = vec![branch.to_string()];
+
+    let mut above = Vec::new();
+    let mut top = branch.to_string();
+    while let Some(child) = github::get_pr_heads_for_base(&top).await.into_iter().next() {
+        if seen.contains(&child) {
+            break;
+        }
+        seen.push(child.clone());
+        above.push(child.clone());
+        top = child;
+    }
+
+    let mut below = Vec::new();
+    let mut bottom = branch.to_string();
+    while let Some(base) = github::get_pr_base(&bottom).await {
+        if seen.contains(&base) {
+            break;
+        }
+        seen.push(base.clone());
+        below.push(base.clone());
+        bottom = base;
+    }
+
+    // The last base without a PR of its own is trunk
+    let trunk = below.last().cloned();
+
+    above
+        .into_iter()
+        .rev()
+        .chain(std::iter::once(branch.to_string()))
+        .chain(below)
+        .map(|name| BranchInfo {
+            is_current: name == current,
+            is_trunk: Some(&name) == trunk.as_ref(),
+            name,
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct StackStatus {
     pub branches: Vec<BranchStatus>,
     pub timestamp: String,
+    /// Full RFC 3339 fetch time
+    pub fetched_at: String,
+    /// Estimated seconds until every running check in the stack finishes
+    pub eta_secs: Option<u64>,
 }
 
 impl StackStatus {
@@ -211,9 +730,28 @@ impl StackStatus {
         Self {
             branches: Vec::new(),
             timestamp: String::new(),
+            fetched_at: String::new(),
+            eta_secs: None,
         }
     }
 
+    /// Aggregate check status of the stack: failed, then running, then passed
+    fn overall(&self) -> github::CheckStatus {
+        use github::CheckStatus;
+
+        let overalls: Vec<CheckStatus> = self
+            .branches
+            .iter()
+            .filter(|b| !b.is_trunk)
+            .filter_map(|b| b.summary.as_ref().map(|s| s.overall))
+            .collect();
+
+        [CheckStatus::Failed, CheckStatus::Running, CheckStatus::Passed]
+            .into_iter()
+            .find(|s| overalls.contains(s))
+            .unwrap_or(CheckStatus::Unknown)
+    }
+
     fn all_complete(&self) -> bool {
         self.branches.iter().all(|b| {
             b.is_trunk
@@ -225,14 +763,33 @@ impl StackStatus {
     }
 }
 
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct BranchStatus {
     pub branch: String,
     pub is_current: bool,
+    /// The branch the stack was requested for (`--branch`, or the current branch)
+    pub is_focus: bool,
     pub is_trunk: bool,
     pub pr: Option<u64>,
+    pub pr_url: Option<String>,
+    pub pr_state: Option<github::PrState>,
+    /// PR head commit that the checks belong to
+    pub head_sha: Option<String>,
+    /// Login of the PR author
+    pub author: Option<String>,
+    pub local_sync: Option<graphite::LocalSync>,
+    pub review: Option<github::ReviewStatus>,
+    pub merge: Option<github::MergeStatus>,
+    /// Review conversations that still need resolving before merge
+    pub unresolved_threads: Option<u64>,
+    /// Present while the PR sits in a GitHub merge queue
+    pub merge_queue: Option<github::MergeQueueStatus>,
+    /// Latest deployment per environment for the PR head (previews, staging)
+    pub deployments: Option<Vec<github::Deployment>>,
     pub checks: Option<Vec<github::Check>>,
     pub summary: Option<github::CheckSummary>,
+    /// Estimated time left for running checks, from recorded durations
+    pub eta: Option<eta::BranchEta>,
 }
 
 #[derive(Debug)]
@@ -0,0 +1,166 @@
+use crate::github::{CheckStatus, PrState, ReviewDecision};
+use crate::{BranchStatus, StackStatus};
+
+/// Render the stack as a Markdown table, top of the stack first
+///
+/// Failing checks link to their logs, so the table can be pasted into PR
+/// descriptions, chat or standup notes as-is.
+pub fn render(status: &StackStatus) -> String {
+    let mut out = String::from("| | Branch | PR | Checks | Review |\n|---|---|---|---|---|\n");
+
+    for branch in status.branches.iter().filter(|b| !b.is_trunk) {
+        let name = if branch.is_focus {
+            format!("**`{}`**", branch.branch)
+        } else {
+            format!("`{}`", branch.branch)
+        };
+
+        let pr = match (branch.pr, &branch.pr_url) {
+            (Some(n), Some(url)) => format!("[#{}]({})", n, url),
+            (Some(n), None) => format!("#{}", n),
+            (None, _) => "—".to_string(),
+        };
+
+        out.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            emoji(branch),
+            escape(&name),
+            escape(&pr),
+            escape(&checks_cell(branch)),
+            escape(&review_cell(branch)),
+        ));
+    }
+
+    out
+}
+
+fn emoji(branch: &BranchStatus) -> &'static str {
+    match branch.pr_state {
+        Some(PrState::Merged) => "🟣",
+        Some(PrState::Closed) => "🚫",
+        _ if branch.pr.is_none() => "⚪",
+        _ => match branch.summary.as_ref().map(|s| s.overall) {
+            Some(CheckStatus::Passed) => "✅",
+            Some(CheckStatus::Failed) => "❌",
+            Some(CheckStatus::Running | CheckStatus::Queued) => "⏳",
+            _ => "⚪",
+        },
+    }
+}
+
+fn checks_cell(branch: &BranchStatus) -> String {
+    match branch.pr_state {
+        Some(PrState::Merged) => return "merged".to_string(),
+        Some(PrState::Closed) => return "closed".to_string(),
+        _ => {}
+    }
+    let Some(ref summary) = branch.summary else {
+        return "—".to_string();
+    };
+
+    let failed: Vec<String> = branch
+        .checks
+        .iter()
+        .flatten()
+        .filter(|c| c.status == CheckStatus::Failed)
+        .map(|c| match c.url {
+            Some(ref url) => format!("[{}]({})", c.name, url),
+            None => c.name.clone(),
+        })
+        .collect();
+
+    let mut cell = summary.text();
+    if !failed.is_empty() {
+        cell.push_str(": ");
+        cell.push_str(&failed.join(", "));
+    }
+    if branch.pr_state == Some(PrState::Draft) {
+        cell.push_str(" (draft)");
+    }
+    cell
+}
+
+fn review_cell(branch: &BranchStatus) -> String {
+    let Some(ref review) = branch.review else {
+        return "—".to_string();
+    };
+    match review.decision {
+        Some(ReviewDecision::Approved) => format!("approved by {}", review.approvals.join(", ")),
+        Some(ReviewDecision::ChangesRequested) => {
+            format!("changes requested by {}", review.changes_requested.join(", "))
+        }
+        Some(ReviewDecision::ReviewRequired) if !review.pending.is_empty() => {
+            format!("waiting on {}", review.pending.join(", "))
+        }
+        Some(ReviewDecision::ReviewRequired) => "review required".to_string(),
+        None => "—".to_string(),
+    }
+}
+
+/// Keep branch, check and reviewer names from breaking the table
+fn escape(cell: &str) -> String {
+    cell.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::Check;
+    use crate::testing;
+
+    #[test]
+    fn test_render_table() {
+        let checks = vec![
+            testing::check("lint", CheckStatus::Passed),
+            Check {
+                url: Some("https://ci/7".to_string()),
+                ..testing::check("test | unit", CheckStatus::Failed)
+            },
+        ];
+
+        let mut status = StackStatus::new();
+        status.branches = vec![
+            BranchStatus {
+                is_focus: true,
+                pr: Some(246),
+                pr_url: Some("https://github.com/acme/app/pull/246".to_string()),
+                pr_state: Some(PrState::Open),
+                ..testing::branch("feature-b", checks)
+            },
+            BranchStatus {
+                branch: "feature-a".to_string(),
+                pr: Some(245),
+                pr_state: Some(PrState::Merged),
+                ..Default::default()
+            },
+            BranchStatus {
+                branch: "main".to_string(),
+                is_trunk: true,
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(
+            render(&status),
+            "| | Branch | PR | Checks | Review |\n\
+             |---|---|---|---|---|\n\
+             | ❌ | **`feature-b`** | [#246](https://github.com/acme/app/pull/246) | 1 failed: [test \\| unit](https://ci/7) | — |\n\
+             | 🟣 | `feature-a` | #245 | merged | — |\n"
+        );
+    }
+
+    #[test]
+    fn test_escapes_every_cell() {
+        let mut status = StackStatus::new();
+        status.branches = vec![BranchStatus {
+            pr: Some(1),
+            pr_url: Some("https://github.com/acme/app/pull/1".to_string()),
+            ..testing::branch("fix|pipe", testing::checks(&[("lint", CheckStatus::Passed)]))
+        }];
+
+        assert_eq!(
+            render(&status).lines().nth(2),
+            Some("| ✅ | `fix\\|pipe` | [#1](https://github.com/acme/app/pull/1) | 1/1 passed | — |")
+        );
+    }
+}
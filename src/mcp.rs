@@ -1,28 +1,81 @@
-use crate::{github, graphite, BranchInfo, BranchStatus, StackStatus};
+use crate::github::CheckStatus;
+use crate::{github, graphite, StackStatus};
 use anyhow::Result;
 use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use rmcp::{
     handler::server::{router::tool::ToolRouter, tool::Parameters},
     model::*,
     schemars, tool, tool_handler, tool_router, ServerHandler, ServiceExt,
     transport::stdio,
+    transport::streamable_http_server::{
+        session::local::LocalSessionManager, StreamableHttpService,
+    },
 };
 use serde::Deserialize;
+use tokio::sync::Mutex;
+
+/// How long a fetched stack status is reused before hitting gt/gh again
+const CACHE_TTL: Duration = Duration::from_secs(10);
+
+/// Stack status cache shared by every session of a server
+#[derive(Clone, Default)]
+pub struct StatusCache {
+    inner: Arc<Mutex<Option<(Instant, StackStatus)>>>,
+}
+
+impl StatusCache {
+    /// Return the cached status if still fresh, otherwise fetch and store a new one
+    async fn get_or_fetch(&self) -> Result<StackStatus> {
+        let mut guard = self.inner.lock().await;
+        if let Some((fetched_at, ref status)) = *guard {
+            if fetched_at.elapsed() < CACHE_TTL {
+                return Ok(status.clone());
+            }
+        }
+
+        let status = fetch_stack_status(None).await?;
+        *guard = Some((Instant::now(), status.clone()));
+        Ok(status)
+    }
+}
 
 /// MCP Server for stack status
 #[derive(Clone)]
 pub struct StackStatusService {
     tool_router: ToolRouter<Self>,
+    cache: StatusCache,
 }
 
 impl StackStatusService {
     pub fn new() -> Self {
+        Self::with_cache(StatusCache::default())
+    }
+
+    /// Create a service that shares an existing status cache
+    pub fn with_cache(cache: StatusCache) -> Self {
         Self {
             tool_router: Self::tool_router(),
+            cache,
         }
     }
 }
 
+/// Request for getting the stack status, all fields optional
+#[derive(Debug, Default, Deserialize, schemars::JsonSchema)]
+pub struct GetStackStatusRequest {
+    /// Return the stack containing this branch (default: the current branch)
+    pub branch: Option<String>,
+    /// Include individual check results for each branch (default: true)
+    pub include_checks: Option<bool>,
+    /// Only return branches and checks with this status (e.g. "failed"); summaries count only those checks
+    pub status: Option<CheckStatus>,
+    /// Return only a one-line summary per branch (default: false)
+    pub summary_only: Option<bool>,
+}
+
 /// Request for getting checks for a specific branch
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct GetBranchChecksRequest {
@@ -33,13 +86,30 @@ pub struct GetBranchChecksRequest {
 #[tool_router]
 impl StackStatusService {
     /// Get the full Graphite stack status including CI check progress for all PRs
-    #[tool(description = "Get the current Graphite stack status including CI check progress for all PRs in the stack")]
-    async fn get_stack_status(&self) -> Result<CallToolResult, ErrorData> {
-        let status = fetch_stack_status().await.map_err(|e| {
+    #[tool(description = "Get the Graphite stack status including CI check progress for all PRs in the stack. Optionally target another branch, drop individual checks, filter by status (e.g. only failed) or request summaries only to keep responses compact.")]
+    async fn get_stack_status(
+        &self,
+        Parameters(req): Parameters<GetStackStatusRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let mut status = self.cache.get_or_fetch().await.map_err(|e| {
             ErrorData::new(ErrorCode(-32000), e.to_string(), None)
         })?;
 
-        let json = serde_json::to_string_pretty(&status).map_err(|e| {
+        // Stacks other than the cached current one are fetched on demand
+        if let Some(ref branch) = req.branch {
+            if status.branches.iter().any(|b| &b.branch == branch) {
+                for b in &mut status.branches {
+                    b.is_focus = &b.branch == branch;
+                }
+            } else {
+                status = fetch_stack_status(Some(branch)).await.map_err(|e| {
+                    ErrorData::new(ErrorCode(-32000), e.to_string(), None)
+                })?;
+            }
+        }
+
+        let result = filter_stack_status(status, &req);
+        let json = serde_json::to_string_pretty(&result).map_err(|e| {
             ErrorData::new(ErrorCode(-32000), e.to_string(), None)
         })?;
 
@@ -58,17 +128,27 @@ impl StackStatusService {
             )]));
         }
 
-        let pr = github::get_pr_for_branch(&req.branch).await;
+        let pr = github::get_pr(&req.branch).await;
         let pr_url = github::get_pr_url(&req.branch).await;
         let checks = github::get_checks(&req.branch).await.map_err(|e| {
             ErrorData::new(ErrorCode(-32000), e.to_string(), None)
         })?;
         let summary = github::summarize_checks(&checks);
+        let activity = match pr {
+            Some(ref p) => github::get_pr_activity(p.number).await,
+            None => None,
+        };
 
         let result = serde_json::json!({
             "branch": req.branch,
-            "pr": pr,
+            "pr": pr.as_ref().map(|p| p.number),
+            "pr_state": pr.as_ref().map(|p| p.state),
             "pr_url": pr_url,
+            "review": pr.as_ref().map(|p| &p.review),
+            "merge": pr.as_ref().map(|p| &p.merge),
+            "unresolved_threads": activity.as_ref().map(|a| a.unresolved_threads),
+            "merge_queue": activity.as_ref().and_then(|a| a.merge_queue.as_ref()),
+            "deployments": activity.as_ref().map(|a| &a.deployments),
             "checks": checks,
             "summary": summary
         });
@@ -139,65 +219,185 @@ pub async fn run_server() -> Result<()> {
     Ok(())
 }
 
-/// Fetch complete stack status (shared with CLI)
-async fn fetch_stack_status() -> Result<StackStatus> {
-    let has_gt = graphite::is_installed().await;
-    let has_gh = github::is_installed().await;
+/// Run the MCP server using streamable HTTP transport (with SSE streaming)
+///
+/// All sessions share one status cache, so several clients can reuse a
+/// single warm server.
+pub async fn run_http_server(addr: SocketAddr) -> Result<()> {
+    if !addr.ip().is_loopback() {
+        eprintln!("Warning: the MCP server has no authentication; anyone who can reach {}", addr);
+        eprintln!("         can read this repo's PRs, checks and branch names.");
+    }
 
-    let mut status = StackStatus {
-        branches: Vec::new(),
-        timestamp: chrono::Local::now().format("%H:%M:%S").to_string(),
-    };
+    let cache = StatusCache::default();
+    let service = StreamableHttpService::new(
+        move || Ok(StackStatusService::with_cache(cache.clone())),
+        LocalSessionManager::default().into(),
+        Default::default(),
+    );
+
+    let router = axum::Router::new()
+        .nest_service("/mcp", service)
+        .layer(axum::middleware::from_fn_with_state(addr, reject_foreign_hosts));
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    eprintln!("MCP server listening on http://{}/mcp", listener.local_addr()?);
+
+    axum::serve(listener, router)
+        .with_graceful_shutdown(async {
+            tokio::signal::ctrl_c().await.ok();
+        })
+        .await?;
+    Ok(())
+}
+
+/// Refuse requests whose `Host` or `Origin` isn't local, so a web page can't reach the server
+/// through DNS rebinding (rmcp doesn't check either header)
+async fn reject_foreign_hosts(
+    axum::extract::State(addr): axum::extract::State<SocketAddr>,
+    req: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    use axum::http::{header, StatusCode};
+    use axum::response::IntoResponse;
 
-    // Get stack from Graphite or fall back to current branch
-    let branches = if has_gt {
-        graphite::get_stack().await?
-    } else {
-        let current = graphite::get_current_branch().await?;
-        vec![BranchInfo {
-            name: current,
-            is_current: true,
-            is_trunk: false,
-        }]
+    let headers = req.headers();
+    let host_ok = headers
+        .get(header::HOST)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|host| is_local_host(host, addr));
+    let origin_ok = headers.get(header::ORIGIN).is_none_or(|v| {
+        v.to_str()
+            .ok()
+            .and_then(|origin| {
+                origin.strip_prefix("http://").or_else(|| origin.strip_prefix("https://"))
+            })
+            .is_some_and(|host| is_local_host(host, addr))
+    });
+    if !host_ok || !origin_ok {
+        return (StatusCode::FORBIDDEN, "non-local Host or Origin\n").into_response();
+    }
+    next.run(req).await
+}
+
+/// Whether a `host[:port]` names this machine: `localhost`, a loopback IP or the bound address
+fn is_local_host(host: &str, addr: SocketAddr) -> bool {
+    let name = match host.strip_prefix('[') {
+        Some(rest) => rest.split(']').next().unwrap_or_default(),
+        None => host.rsplit_once(':').map_or(host, |(name, _)| name),
     };
+    name.eq_ignore_ascii_case("localhost")
+        || name
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback() || ip == addr.ip())
+}
 
-    // Get PR and check status for each branch
-    for branch in branches {
-        if branch.is_trunk {
-            status.branches.push(BranchStatus {
-                branch: branch.name,
-                is_current: branch.is_current,
-                is_trunk: true,
-                pr: None,
-                checks: None,
-                summary: None,
-            });
-            continue;
+/// Narrow a stack status down to what a get_stack_status request asked for
+fn filter_stack_status(mut status: StackStatus, req: &GetStackStatusRequest) -> serde_json::Value {
+    if let Some(filter) = req.status {
+        status.branches.retain(|b| {
+            b.summary.as_ref().map(|s| s.overall) == Some(filter)
+                || b.checks
+                    .as_ref()
+                    .is_some_and(|c| c.iter().any(|check| check.status == filter))
+        });
+        for branch in &mut status.branches {
+            if let Some(ref mut checks) = branch.checks {
+                checks.retain(|c| c.status == filter);
+                branch.summary = Some(github::summarize_checks(checks));
+            }
         }
+    }
 
-        let (pr, checks) = if has_gh {
-            let pr = github::get_pr_for_branch(&branch.name).await;
-            let checks = if pr.is_some() {
-                Some(github::get_checks(&branch.name).await?)
-            } else {
-                None
-            };
-            (pr, checks)
-        } else {
-            (None, None)
-        };
+    if req.summary_only.unwrap_or(false) {
+        let branches: Vec<_> = status
+            .branches
+            .iter()
+            .map(|b| {
+                serde_json::json!({
+                    "branch": b.branch,
+                    "is_current": b.is_current,
+                    "is_focus": b.is_focus,
+                    "pr": b.pr,
+                    "pr_state": b.pr_state,
+                    "stale_checks": b.local_sync.as_ref().map(|s| s.stale_checks),
+                    "unpushed": b.local_sync.as_ref().and_then(|s| s.unpushed),
+                    "behind": b.local_sync.as_ref().and_then(|s| s.behind),
+                    "review": b.review.as_ref().and_then(|r| r.decision),
+                    "merge": b.merge.as_ref().map(|m| m.state),
+                    "unresolved_threads": b.unresolved_threads,
+                    "merge_queue": b.merge_queue.as_ref().map(|q| {
+                        serde_json::json!({"position": q.position, "state": q.state})
+                    }),
+                    "status": b.summary.as_ref().map(|s| s.overall),
+                    "summary": b.summary.as_ref().map(|s| s.text()),
+                })
+            })
+            .collect();
+        return serde_json::json!({
+            "branches": branches,
+            "timestamp": status.timestamp,
+        });
+    }
 
-        let summary = checks.as_ref().map(|c| github::summarize_checks(c));
+    if !req.include_checks.unwrap_or(true) {
+        for branch in &mut status.branches {
+            branch.checks = None;
+        }
+    }
 
-        status.branches.push(BranchStatus {
-            branch: branch.name,
-            is_current: branch.is_current,
-            is_trunk: false,
-            pr,
-            checks,
-            summary,
-        });
+    serde_json::to_value(&status).unwrap_or_default()
+}
+
+/// Fetch complete stack status (shared with CLI)
+async fn fetch_stack_status(branch: Option<&str>) -> Result<StackStatus> {
+    if let Some(status) = crate::daemon::query(branch).await {
+        return Ok(status);
+    }
+    let has_gt = graphite::is_installed().await;
+    let has_gh = github::is_installed().await;
+    crate::fetch_stack_status(branch, has_gt, has_gh).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn test_is_local_host() {
+        let loopback: SocketAddr = "127.0.0.1:8765".parse().unwrap();
+        assert!(is_local_host("localhost:8765", loopback));
+        assert!(is_local_host("127.0.0.1:8765", loopback));
+        assert!(is_local_host("[::1]:8765", loopback));
+        assert!(is_local_host("localhost", loopback));
+        assert!(!is_local_host("attacker.example:8765", loopback));
+        assert!(!is_local_host("localhost.attacker.example", loopback));
+
+        // A public bind still accepts its own address, which can't be rebound
+        let public: SocketAddr = "192.168.1.5:8765".parse().unwrap();
+        assert!(is_local_host("192.168.1.5:8765", public));
+        assert!(!is_local_host("192.168.1.6:8765", public));
     }
 
-    Ok(status)
+    #[test]
+    fn test_status_filter_recounts_summary() {
+        let mut status = StackStatus::new();
+        status.branches = vec![
+            testing::branch(
+                "feature-b",
+                testing::checks(&[("lint", CheckStatus::Passed), ("test", CheckStatus::Failed)]),
+            ),
+            testing::branch("feature-a", testing::checks(&[("lint", CheckStatus::Passed)])),
+        ];
+        let req = GetStackStatusRequest {
+            status: Some(CheckStatus::Failed),
+            ..Default::default()
+        };
+
+        let result = filter_stack_status(status, &req);
+        let branches = result["branches"].as_array().unwrap();
+        assert_eq!(branches.len(), 1);
+        assert_eq!(branches[0]["summary"]["total"], 1);
+        assert_eq!(branches[0]["summary"]["passed"], 0);
+    }
 }
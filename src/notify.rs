@@ -0,0 +1,152 @@
+use crate::events::Event;
+use std::io::Write;
+use tokio::process::Command;
+
+/// A notification to show the user
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub title: String,
+    pub body: String,
+}
+
+/// Notification for a branch's checks finishing or the stack settling, if any
+pub fn for_event(event: &Event) -> Option<Notification> {
+    let pr = |pr: &Option<u64>| pr.map(|n| format!(" #{}", n)).unwrap_or_default();
+
+    match event {
+        Event::ChecksPassed { branch, pr: n, total } => Some(Notification {
+            title: format!("✓ {}{} passed", branch, pr(n)),
+            body: format!("{}/{} passed", total, total),
+        }),
+        Event::ChecksFailed { branch, pr: n, failed } => Some(Notification {
+            title: format!("✗ {}{} failed", branch, pr(n)),
+            body: failed.join(", "),
+        }),
+        Event::StackSettled { failing } => Some(Notification {
+            title: "Stack settled".to_string(),
+            body: if failing.is_empty() {
+                "All checks complete".to_string()
+            } else {
+                format!("Failing: {}", failing.join(", "))
+            },
+        }),
+        _ => None,
+    }
+}
+
+/// How notifications reach the user
+#[derive(Debug, Clone, Copy)]
+pub enum Notifier {
+    /// `notify-send` (freedesktop notifications over D-Bus)
+    NotifySend,
+    /// `osascript` on macOS
+    Osascript,
+    /// An OSC 9 or 777 escape code, for terminals that show them
+    Terminal,
+}
+
+impl Notifier {
+    /// Pick the best available notification mechanism
+    pub async fn detect() -> Self {
+        if cfg!(target_os = "macos") && has_command("osascript", "-e", "return").await {
+            Notifier::Osascript
+        } else if has_command("notify-send", "--version", "").await {
+            Notifier::NotifySend
+        } else {
+            Notifier::Terminal
+        }
+    }
+
+    pub async fn send(self, note: &Notification) {
+        let delivered = match self {
+            Notifier::NotifySend => Command::new("notify-send")
+                .args(["--app-name", "stack-status", &note.title, &note.body])
+                .status()
+                .await
+                .is_ok_and(|s| s.success()),
+            Notifier::Osascript => {
+                let script = format!(
+                    "display notification \"{}\" with title \"{}\"",
+                    applescript_escape(&note.body),
+                    applescript_escape(&note.title)
+                );
+                Command::new("osascript")
+                    .args(["-e", &script])
+                    .status()
+                    .await
+                    .is_ok_and(|s| s.success())
+            }
+            Notifier::Terminal => false,
+        };
+
+        if !delivered {
+            terminal_notify(note);
+        }
+    }
+}
+
+async fn has_command(cmd: &str, arg1: &str, arg2: &str) -> bool {
+    Command::new(cmd)
+        .args([arg1, arg2].into_iter().filter(|a| !a.is_empty()))
+        .output()
+        .await
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+fn applescript_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Emit one OSC notification: 777 for terminals that only speak it (urxvt, foot, VTE), else 9
+///
+/// A plain bell follows, so terminals that ignore both sequences still get the user's attention.
+/// Written to the terminal rather than stdout, so `--json` output stays clean.
+fn terminal_notify(note: &Notification) {
+    let clean = |s: &str| s.replace(['\x07', '\x1b', ';'], " ");
+    let (title, body) = (clean(&note.title), clean(&note.body));
+
+    let term = std::env::var("TERM").unwrap_or_default();
+    let osc777 = std::env::var_os("VTE_VERSION").is_some()
+        || term.starts_with("rxvt")
+        || term.starts_with("foot");
+    let sequence = if osc777 {
+        format!("\x1b]777;notify;{};{}\x07\x07", title, body)
+    } else {
+        format!("\x1b]9;{}: {}\x07\x07", title, body)
+    };
+
+    let written = std::fs::OpenOptions::new()
+        .write(true)
+        .open("/dev/tty")
+        .and_then(|mut tty| tty.write_all(sequence.as_bytes()));
+    if written.is_err() {
+        std::io::stderr().write_all(sequence.as_bytes()).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_event() {
+        let failed = Event::ChecksFailed {
+            branch: "feature".to_string(),
+            pr: Some(12),
+            failed: vec!["test".to_string(), "lint".to_string()],
+        };
+        assert_eq!(
+            for_event(&failed),
+            Some(Notification {
+                title: "✗ feature #12 failed".to_string(),
+                body: "test, lint".to_string(),
+            })
+        );
+
+        let added = Event::BranchAdded {
+            branch: "feature".to_string(),
+        };
+        assert_eq!(for_event(&added), None);
+    }
+}
@@ -0,0 +1,88 @@
+use crate::github::{CheckStatus, PrState};
+use crate::graphite;
+use crate::store::{BranchCache, CachedBranch};
+use chrono::{DateTime, Utc};
+
+/// Print the prompt segment for the current branch, from the cache only
+///
+/// Prints nothing outside a repo or when the branch has no cached PR, so
+/// prompts can call this unconditionally.
+pub async fn run() {
+    let Some((toplevel, branch)) = graphite::get_checkout().await else {
+        return;
+    };
+    if let Some(segment) = BranchCache::for_checkout(&toplevel)
+        .get(&branch)
+        .and_then(|cached| segment(&cached, Utc::now()))
+    {
+        println!("{}", segment);
+    }
+}
+
+/// Compact status such as `#247 ✓`, `#247 ◐3/5` or `#247 ✗1`
+///
+/// Check results that are too old to trust show as `#247 ?`.
+pub fn segment(cached: &CachedBranch, now: DateTime<Utc>) -> Option<String> {
+    let pr = cached.pr?;
+
+    let state = match cached.pr_state {
+        Some(PrState::Merged) => Some("merged".to_string()),
+        Some(PrState::Closed) => Some("closed".to_string()),
+        _ if cached.is_stale(now) => Some("?".to_string()),
+        _ => cached.summary.as_ref().and_then(|s| match s.overall {
+            CheckStatus::Passed => Some("✓".to_string()),
+            CheckStatus::Failed => Some(format!("✗{}", s.failed)),
+            CheckStatus::Running | CheckStatus::Queued => {
+                Some(format!("◐{}/{}", s.passed, s.total))
+            }
+            _ => None,
+        }),
+    };
+
+    Some(match state {
+        Some(state) => format!("#{} {}", pr, state),
+        None => format!("#{}", pr),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github;
+    use crate::testing;
+
+    fn cached(statuses: &[CheckStatus]) -> CachedBranch {
+        let checks: Vec<_> = statuses.iter().map(|&status| testing::check("check", status)).collect();
+
+        CachedBranch {
+            fetched_at: "2024-01-01T12:00:00Z".to_string(),
+            pr: Some(247),
+            pr_state: Some(PrState::Open),
+            summary: Some(github::summarize_checks(&checks)),
+        }
+    }
+
+    #[test]
+    fn test_segment() {
+        use CheckStatus::*;
+        let now = DateTime::parse_from_rfc3339("2024-01-01T12:05:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert_eq!(segment(&cached(&[Passed, Passed]), now).as_deref(), Some("#247 ✓"));
+        assert_eq!(
+            segment(&cached(&[Passed, Passed, Passed, Running, Queued]), now).as_deref(),
+            Some("#247 ◐3/5")
+        );
+        assert_eq!(segment(&cached(&[Failed, Running]), now).as_deref(), Some("#247 ✗1"));
+        assert_eq!(segment(&cached(&[]), now).as_deref(), Some("#247"));
+
+        let mut merged = cached(&[]);
+        merged.pr_state = Some(PrState::Merged);
+        assert_eq!(segment(&merged, now).as_deref(), Some("#247 merged"));
+
+        let later = now + chrono::Duration::hours(2);
+        assert_eq!(segment(&cached(&[Passed]), later).as_deref(), Some("#247 ?"));
+        assert_eq!(segment(&merged, later).as_deref(), Some("#247 merged"));
+    }
+}
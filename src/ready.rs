@@ -0,0 +1,320 @@
+use crate::github::{CheckStatus, MergeState, PrState, ReviewDecision};
+use crate::{BranchStatus, StackStatus};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Reason a PR cannot land right now
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Blocker {
+    NoPr,
+    Draft,
+    Closed,
+    ChecksFailing { checks: Vec<String> },
+    ChecksPending { checks: Vec<String> },
+    ReviewRequired,
+    ChangesRequested { reviewers: Vec<String> },
+    UnresolvedThreads { count: u64 },
+    Conflicts,
+    BehindBase,
+    BlockedByProtection,
+    ParentNotLanded { parent: String },
+}
+
+impl Blocker {
+    pub fn describe(&self) -> String {
+        match self {
+            Blocker::NoPr => "no PR opened".to_string(),
+            Blocker::Draft => "PR is a draft".to_string(),
+            Blocker::Closed => "PR was closed without merging".to_string(),
+            Blocker::ChecksFailing { checks } => {
+                format!("required checks failing: {}", checks.join(", "))
+            }
+            Blocker::ChecksPending { checks } => {
+                format!("required checks not finished: {}", checks.join(", "))
+            }
+            Blocker::ReviewRequired => "review required".to_string(),
+            Blocker::ChangesRequested { reviewers } => {
+                format!("changes requested by {}", reviewers.join(", "))
+            }
+            Blocker::UnresolvedThreads { count } => {
+                format!("{} unresolved review thread(s)", count)
+            }
+            Blocker::Conflicts => "merge conflicts with base".to_string(),
+            Blocker::BehindBase => "branch is behind its base".to_string(),
+            Blocker::BlockedByProtection => "blocked by branch protection".to_string(),
+            Blocker::ParentNotLanded { parent } => format!("waiting on {} to land", parent),
+        }
+    }
+}
+
+/// Merge readiness of a single branch
+#[derive(Debug, Serialize, Clone)]
+pub struct BranchReadiness {
+    pub branch: String,
+    pub pr: Option<u64>,
+    /// PR is already merged
+    pub landed: bool,
+    /// PR can be merged now (once the PRs below it have landed)
+    pub landable: bool,
+    pub blockers: Vec<Blocker>,
+}
+
+/// Merge readiness of a whole stack
+#[derive(Debug, Serialize, Clone)]
+pub struct ReadyReport {
+    /// Branches from the bottom of the stack up, trunk excluded
+    pub branches: Vec<BranchReadiness>,
+    /// Number of unmerged PRs, counted from the bottom, that can land right now
+    pub landable: usize,
+    /// First branch that cannot land, if any
+    pub first_blocked: Option<String>,
+}
+
+impl ReadyReport {
+    pub fn first_blocked(&self) -> Option<&BranchReadiness> {
+        let name = self.first_blocked.as_ref()?;
+        self.branches.iter().find(|b| &b.branch == name)
+    }
+}
+
+/// Walk the stack bottom-up and work out which PRs can land
+///
+/// Every reported check that isn't skipped must pass. `required` maps a
+/// branch to the check names its base branch protection requires; those that
+/// haven't reported yet count as pending.
+pub fn evaluate(status: &StackStatus, required: &HashMap<String, Vec<String>>) -> ReadyReport {
+    let mut branches = Vec::new();
+    let mut parent: Option<(&str, bool)> = None;
+
+    for branch in status.branches.iter().rev().filter(|b| !b.is_trunk) {
+        if branch.pr_state == Some(PrState::Merged) {
+            branches.push(BranchReadiness {
+                branch: branch.branch.clone(),
+                pr: branch.pr,
+                landed: true,
+                landable: false,
+                blockers: Vec::new(),
+            });
+            parent = Some((&branch.branch, true));
+            continue;
+        }
+
+        let mut blockers = branch_blockers(branch, required.get(&branch.branch));
+        if let Some((name, false)) = parent {
+            blockers.push(Blocker::ParentNotLanded {
+                parent: name.to_string(),
+            });
+        }
+
+        let landable = blockers.is_empty();
+        parent = Some((&branch.branch, landable));
+        branches.push(BranchReadiness {
+            branch: branch.branch.clone(),
+            pr: branch.pr,
+            landed: false,
+            landable,
+            blockers,
+        });
+    }
+
+    ReadyReport {
+        landable: branches.iter().filter(|b| b.landable).count(),
+        first_blocked: branches
+            .iter()
+            .find(|b| !b.landed && !b.landable)
+            .map(|b| b.branch.clone()),
+        branches,
+    }
+}
+
+/// Blockers that come from the branch's own PR, ignoring the rest of the stack
+fn branch_blockers(branch: &BranchStatus, required: Option<&Vec<String>>) -> Vec<Blocker> {
+    if branch.pr.is_none() {
+        return vec![Blocker::NoPr];
+    }
+
+    let mut blockers = Vec::new();
+
+    match branch.pr_state {
+        Some(PrState::Closed) => return vec![Blocker::Closed],
+        Some(PrState::Draft) => blockers.push(Blocker::Draft),
+        _ => {}
+    }
+
+    if let Some(ref checks) = branch.checks {
+        let mut failing = Vec::new();
+        let mut pending = Vec::new();
+        for check in checks {
+            match check.status {
+                CheckStatus::Failed | CheckStatus::Cancelled | CheckStatus::Unknown => {
+                    failing.push(check.name.clone())
+                }
+                CheckStatus::Running | CheckStatus::Queued => pending.push(check.name.clone()),
+                _ => {}
+            }
+        }
+        // Required checks that haven't reported yet are as good as queued
+        for name in required.into_iter().flatten() {
+            if !checks.iter().any(|c| &c.name == name) {
+                pending.push(name.clone());
+            }
+        }
+
+        if !failing.is_empty() {
+            blockers.push(Blocker::ChecksFailing { checks: failing });
+        }
+        if !pending.is_empty() {
+            blockers.push(Blocker::ChecksPending { checks: pending });
+        }
+    }
+
+    if let Some(ref review) = branch.review {
+        match review.decision {
+            Some(ReviewDecision::ChangesRequested) => blockers.push(Blocker::ChangesRequested {
+                reviewers: review.changes_requested.clone(),
+            }),
+            Some(ReviewDecision::ReviewRequired) => blockers.push(Blocker::ReviewRequired),
+            _ => {}
+        }
+    }
+
+    if let Some(count) = branch.unresolved_threads.filter(|&n| n > 0) {
+        blockers.push(Blocker::UnresolvedThreads { count });
+    }
+
+    if let Some(ref merge) = branch.merge {
+        if merge.has_conflicts {
+            blockers.push(Blocker::Conflicts);
+        } else if merge.behind_base {
+            blockers.push(Blocker::BehindBase);
+        } else if merge.state == MergeState::Blocked && blockers.is_empty() {
+            // Protection rules we can't see more detail about
+            blockers.push(Blocker::BlockedByProtection);
+        }
+    }
+
+    blockers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::ReviewStatus;
+    use crate::testing;
+
+    fn branch(name: &str, pr: u64, checks: &[(&str, CheckStatus)]) -> BranchStatus {
+        BranchStatus {
+            pr: Some(pr),
+            pr_state: Some(PrState::Open),
+            review: Some(ReviewStatus {
+                decision: Some(ReviewDecision::Approved),
+                approvals: vec!["alice".to_string()],
+                changes_requested: Vec::new(),
+                pending: Vec::new(),
+            }),
+            unresolved_threads: Some(0),
+            ..testing::branch(name, testing::checks(checks))
+        }
+    }
+
+    #[test]
+    fn test_evaluate_stops_at_first_blocked_pr() {
+        let mut status = StackStatus::new();
+        status.branches = vec![
+            branch("feature-c", 3, &[("test", CheckStatus::Passed)]),
+            branch("feature-b", 2, &[("test", CheckStatus::Failed), ("lint", CheckStatus::Skipped)]),
+            branch("feature-a", 1, &[("test", CheckStatus::Passed)]),
+        ];
+
+        let required = HashMap::from([("feature-b".to_string(), vec!["test".to_string()])]);
+        let report = evaluate(&status, &required);
+
+        assert_eq!(report.landable, 1);
+        assert_eq!(report.first_blocked.as_deref(), Some("feature-b"));
+        assert_eq!(
+            report.branches[1].blockers,
+            vec![Blocker::ChecksFailing {
+                checks: vec!["test".to_string()]
+            }]
+        );
+        assert_eq!(
+            report.branches[2].blockers,
+            vec![Blocker::ParentNotLanded {
+                parent: "feature-b".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_evaluate_skips_merged_parent() {
+        let mut merged = branch("feature-a", 1, &[]);
+        merged.pr_state = Some(PrState::Merged);
+
+        let mut status = StackStatus::new();
+        status.branches = vec![branch("feature-b", 2, &[("test", CheckStatus::Passed)]), merged];
+
+        let report = evaluate(&status, &HashMap::new());
+        assert!(report.branches[0].landed);
+        assert!(report.branches[1].landable);
+        assert_eq!(report.first_blocked, None);
+    }
+
+    #[test]
+    fn test_missing_required_check_is_pending() {
+        let mut status = StackStatus::new();
+        status.branches = vec![branch("feature-a", 1, &[("lint", CheckStatus::Passed)])];
+
+        let required = HashMap::from([(
+            "feature-a".to_string(),
+            vec!["lint".to_string(), "deploy-preview".to_string()],
+        )]);
+        let report = evaluate(&status, &required);
+
+        assert!(!report.branches[0].landable);
+        assert_eq!(
+            report.branches[0].blockers,
+            vec![Blocker::ChecksPending {
+                checks: vec!["deploy-preview".to_string()]
+            }]
+        );
+    }
+
+    #[test]
+    fn test_required_checks_pending_before_any_report() {
+        // A fresh head: gh reports no checks at all, but protection requires two
+        let mut status = StackStatus::new();
+        status.branches = vec![branch("feature-a", 1, &[])];
+
+        let required = HashMap::from([(
+            "feature-a".to_string(),
+            vec!["test".to_string(), "lint".to_string()],
+        )]);
+        let report = evaluate(&status, &required);
+
+        assert_eq!(
+            report.branches[0].blockers,
+            vec![Blocker::ChecksPending {
+                checks: vec!["test".to_string(), "lint".to_string()]
+            }]
+        );
+    }
+
+    #[test]
+    fn test_unrequired_failing_check_blocks() {
+        let mut status = StackStatus::new();
+        status.branches = vec![branch(
+            "feature-a",
+            1,
+            &[("test", CheckStatus::Passed), ("e2e", CheckStatus::Failed)],
+        )];
+
+        let report = evaluate(&status, &HashMap::from([("feature-a".to_string(), Vec::new())]));
+        assert_eq!(
+            report.branches[0].blockers,
+            vec![Blocker::ChecksFailing {
+                checks: vec!["e2e".to_string()]
+            }]
+        );
+    }
+}
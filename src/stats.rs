@@ -0,0 +1,249 @@
+use crate::github::CheckStatus;
+use crate::store::Snapshot;
+use anyhow::{bail, Result};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Which recorded runs to include in the statistics
+#[derive(Debug, Default)]
+pub struct StatsFilter<'a> {
+    pub branch: Option<&'a str>,
+    pub author: Option<&'a str>,
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// Aggregated CI statistics for one check
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckStats {
+    pub name: String,
+    /// Distinct runs seen, including ones that never finished while recorded
+    pub runs: usize,
+    pub passed: usize,
+    pub failed: usize,
+    /// Share of finished runs that passed, from 0 to 1
+    pub pass_rate: Option<f64>,
+    pub median_secs: Option<u64>,
+    pub p90_secs: Option<u64>,
+    /// Median time from first seen queued to started
+    pub median_queue_secs: Option<u64>,
+    /// Total runtime of finished runs
+    pub ci_secs: u64,
+}
+
+/// CI statistics over the recorded history, busiest checks first
+#[derive(Debug, Clone, Serialize)]
+pub struct StatsReport {
+    pub since: Option<String>,
+    pub checks: Vec<CheckStats>,
+    pub total_ci_secs: u64,
+}
+
+/// One check run, pieced together from the snapshots it appeared in
+#[derive(Debug, Default)]
+struct Run {
+    outcome: Option<CheckStatus>,
+    duration_secs: Option<u64>,
+    first_queued: Option<DateTime<Utc>>,
+    started: Option<DateTime<Utc>>,
+}
+
+/// Aggregate recorded snapshots into per-check statistics
+pub fn compute(snapshots: &[Snapshot], filter: &StatsFilter) -> StatsReport {
+    // check name -> run id -> run; runs without a URL are keyed by head SHA
+    let mut runs: HashMap<&str, HashMap<String, Run>> = HashMap::new();
+
+    for snapshot in snapshots {
+        let Some(fetched_at) = parse_time(&snapshot.fetched_at) else {
+            continue;
+        };
+        if filter.since.is_some_and(|since| fetched_at < since) {
+            continue;
+        }
+
+        for branch in &snapshot.branches {
+            if filter.branch.is_some_and(|b| b != branch.branch) {
+                continue;
+            }
+            if filter.author.is_some_and(|a| branch.author.as_deref() != Some(a)) {
+                continue;
+            }
+
+            for check in &branch.checks {
+                let id = check.url.clone().unwrap_or_else(|| {
+                    format!("{}@{}", branch.branch, branch.head_sha.as_deref().unwrap_or(""))
+                });
+                let run = runs.entry(&check.name).or_default().entry(id).or_default();
+
+                match check.status {
+                    CheckStatus::Queued => {
+                        run.first_queued.get_or_insert(fetched_at);
+                    }
+                    CheckStatus::Passed | CheckStatus::Failed => {
+                        run.outcome = Some(check.status);
+                        run.duration_secs = check.duration_secs;
+                    }
+                    _ => {}
+                }
+                if let Some(started) = check.started_at.as_deref().and_then(parse_time) {
+                    run.started = Some(started);
+                }
+            }
+        }
+    }
+
+    let mut checks: Vec<CheckStats> = runs
+        .into_iter()
+        .map(|(name, runs)| {
+            let runs: Vec<Run> = runs.into_values().collect();
+            let passed = count(&runs, CheckStatus::Passed);
+            let failed = count(&runs, CheckStatus::Failed);
+
+            let mut durations: Vec<u64> = runs
+                .iter()
+                .filter(|r| r.outcome.is_some())
+                .filter_map(|r| r.duration_secs)
+                .collect();
+            durations.sort_unstable();
+
+            let mut queue: Vec<u64> = runs
+                .iter()
+                .filter_map(|r| {
+                    let secs = (r.started? - r.first_queued?).num_seconds();
+                    (secs >= 0).then_some(secs as u64)
+                })
+                .collect();
+            queue.sort_unstable();
+
+            CheckStats {
+                name: name.to_string(),
+                runs: runs.len(),
+                passed,
+                failed,
+                pass_rate: (passed + failed > 0)
+                    .then(|| passed as f64 / (passed + failed) as f64),
+                median_secs: percentile(&durations, 50),
+                p90_secs: percentile(&durations, 90),
+                median_queue_secs: percentile(&queue, 50),
+                ci_secs: durations.iter().sum(),
+            }
+        })
+        .collect();
+
+    checks.sort_by(|a, b| b.ci_secs.cmp(&a.ci_secs).then_with(|| a.name.cmp(&b.name)));
+
+    StatsReport {
+        since: filter.since.map(|t| t.to_rfc3339()),
+        total_ci_secs: checks.iter().map(|c| c.ci_secs).sum(),
+        checks,
+    }
+}
+
+fn count(runs: &[Run], outcome: CheckStatus) -> usize {
+    runs.iter().filter(|r| r.outcome == Some(outcome)).count()
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[u64], pct: usize) -> Option<u64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    Some(sorted[rank - 1])
+}
+
+fn parse_time(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Parse `--since` as a relative window (`90m`, `24h`, `7d`, `2w`) or a date
+pub fn parse_since(s: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    if let Some(t) = parse_time(s) {
+        return Ok(t);
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+
+    // Split before the last char, not byte, so non-ASCII input can't panic
+    let split = s.char_indices().last().map_or(0, |(i, _)| i);
+    let (num, unit) = s.split_at(split);
+    let window = match (num.parse::<i64>(), unit) {
+        (Ok(n), _) if n <= 0 => None,
+        (Ok(n), "m") => Duration::try_minutes(n),
+        (Ok(n), "h") => Duration::try_hours(n),
+        (Ok(n), "d") => Duration::try_days(n),
+        (Ok(n), "w") => Duration::try_weeks(n),
+        _ => None,
+    };
+    // Windows too large for chrono (or reaching before its earliest date) are rejected too
+    match window.and_then(|w| now.checked_sub_signed(w)) {
+        Some(since) => Ok(since),
+        None => bail!("invalid --since '{}': use e.g. 24h, 7d, 2w or YYYY-MM-DD", s),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::Check;
+    use crate::testing;
+
+    fn check(name: &str, status: CheckStatus, run: &str, secs: Option<u64>) -> Check {
+        Check {
+            started_at: (status != CheckStatus::Queued).then(|| "2024-01-01T12:01:00Z".to_string()),
+            duration_secs: secs,
+            url: Some(run.to_string()),
+            ..testing::check(name, status)
+        }
+    }
+
+    fn snapshot(at: &str, author: &str, checks: Vec<Check>) -> Snapshot {
+        let mut snapshot = testing::snapshot(at, "aaa", checks);
+        snapshot.branches[0].author = Some(author.to_string());
+        snapshot
+    }
+
+    #[test]
+    fn test_compute_aggregates_runs() {
+        let snapshots = vec![
+            snapshot("2024-01-01T12:00:00Z", "alice", vec![check("test", CheckStatus::Queued, "r1", None)]),
+            snapshot("2024-01-01T12:02:00Z", "alice", vec![check("test", CheckStatus::Running, "r1", None)]),
+            snapshot("2024-01-01T12:10:00Z", "alice", vec![check("test", CheckStatus::Failed, "r1", Some(300))]),
+            snapshot("2024-01-01T13:00:00Z", "alice", vec![check("test", CheckStatus::Passed, "r2", Some(100))]),
+            snapshot("2024-01-01T14:00:00Z", "bob", vec![check("test", CheckStatus::Passed, "r3", Some(200))]),
+        ];
+
+        let report = compute(&snapshots, &StatsFilter::default());
+        let test = &report.checks[0];
+        assert_eq!((test.runs, test.passed, test.failed), (3, 2, 1));
+        assert_eq!(test.median_secs, Some(200));
+        assert_eq!(test.p90_secs, Some(300));
+        assert_eq!(test.median_queue_secs, Some(60));
+        assert_eq!(report.total_ci_secs, 600);
+
+        let alice = compute(
+            &snapshots,
+            &StatsFilter {
+                author: Some("alice"),
+                ..Default::default()
+            },
+        );
+        assert_eq!(alice.checks[0].runs, 2);
+    }
+
+    #[test]
+    fn test_parse_since() {
+        let now = parse_time("2024-01-08T00:00:00Z").unwrap();
+        assert_eq!(parse_since("7d", now).unwrap(), parse_time("2024-01-01T00:00:00Z").unwrap());
+        assert_eq!(parse_since("2024-01-05", now).unwrap(), parse_time("2024-01-05T00:00:00Z").unwrap());
+        assert!(parse_since("soon", now).is_err());
+        assert!(parse_since("7é", now).is_err());
+        assert!(parse_since("", now).is_err());
+        assert!(parse_since("99999999999999999w", now).is_err());
+        assert!(parse_since("-5d", now).is_err());
+        assert!(parse_since("0h", now).is_err());
+    }
+}
@@ -0,0 +1,443 @@
+use crate::github::{self, Check, CheckStatus, CheckSummary, PrState};
+use crate::StackStatus;
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// Number of recent durations kept per check
+const MAX_SAMPLES: usize = 50;
+
+/// Directory for persisted data (e.g. `~/.local/share/stack-status`)
+pub fn data_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("stack-status"))
+}
+
+/// On-disk layout of `durations.json`: repo -> check name -> samples
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DurationFile {
+    repos: HashMap<String, HashMap<String, Vec<DurationSample>>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DurationSample {
+    /// Details URL of the run, used to avoid recording a run twice
+    run: Option<String>,
+    secs: u64,
+}
+
+/// Completed check durations for one repo, persisted across runs
+pub struct DurationHistory {
+    path: Option<PathBuf>,
+    repo: String,
+    file: DurationFile,
+}
+
+impl DurationHistory {
+    /// Load recorded durations, starting empty if there's no readable history
+    pub fn load(repo: &str) -> Self {
+        let path = data_dir().map(|d| d.join("durations.json"));
+        let file = path
+            .as_ref()
+            .and_then(|p| fs::read(p).ok())
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+
+        Self {
+            path,
+            repo: repo.to_string(),
+            file,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn in_memory(repo: &str) -> Self {
+        Self {
+            path: None,
+            repo: repo.to_string(),
+            file: DurationFile::default(),
+        }
+    }
+
+    /// Record durations of passed checks. Returns true if anything new was added.
+    ///
+    /// Failed runs are skipped: they often stop early and would drag the median down.
+    pub fn record(&mut self, checks: &[Check]) -> bool {
+        let repo = self.file.repos.entry(self.repo.clone()).or_default();
+        let mut changed = false;
+
+        for check in checks {
+            let passed = check.status == CheckStatus::Passed;
+            let Some(secs) = check.duration_secs.filter(|_| passed) else {
+                continue;
+            };
+
+            let samples = repo.entry(check.name.clone()).or_default();
+            if check.url.is_some() && samples.iter().any(|s| s.run == check.url) {
+                continue;
+            }
+
+            samples.push(DurationSample {
+                run: check.url.clone(),
+                secs,
+            });
+            if samples.len() > MAX_SAMPLES {
+                samples.remove(0);
+            }
+            changed = true;
+        }
+
+        changed
+    }
+
+    /// Typical duration of a check, as the median of recorded runs
+    pub fn expected_secs(&self, check: &str) -> Option<u64> {
+        let samples = self.file.repos.get(&self.repo)?.get(check)?;
+        if samples.is_empty() {
+            return None;
+        }
+
+        let mut secs: Vec<u64> = samples.iter().map(|s| s.secs).collect();
+        secs.sort_unstable();
+        Some(secs[secs.len() / 2])
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let Some(ref path) = self.path else {
+            return Ok(());
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Write then rename, so concurrent readers never see a half-written file
+        let tmp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+        fs::write(&tmp, serde_json::to_vec(&self.file)?)?;
+        fs::rename(tmp, path)?;
+        Ok(())
+    }
+}
+
+/// A recorded stack status, trimmed to what history questions need
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// RFC 3339 fetch time
+    pub fetched_at: String,
+    pub branches: Vec<BranchSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BranchSnapshot {
+    pub branch: String,
+    pub pr: Option<u64>,
+    pub pr_state: Option<PrState>,
+    pub head_sha: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
+    pub checks: Vec<Check>,
+}
+
+impl Snapshot {
+    pub fn from_status(status: &StackStatus) -> Self {
+        Self {
+            fetched_at: status.fetched_at.clone(),
+            branches: status
+                .branches
+                .iter()
+                .filter(|b| !b.is_trunk && b.pr.is_some())
+                .map(|b| BranchSnapshot {
+                    branch: b.branch.clone(),
+                    pr: b.pr,
+                    pr_state: b.pr_state,
+                    head_sha: b.head_sha.clone(),
+                    author: b.author.clone(),
+                    // Expected durations and flakiness come from history, not from CI
+                    checks: b
+                        .checks
+                        .iter()
+                        .flatten()
+                        .map(|c| Check {
+                            expected_secs: None,
+                            flaky: None,
+                            ..c.clone()
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Same branches, SHAs and check results, regardless of when fetched
+    fn same_state(&self, other: &Snapshot) -> bool {
+        self.branches == other.branches
+    }
+}
+
+/// Repo identifier made safe to use as a file name
+pub fn file_name(repo: &str) -> String {
+    repo.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect()
+}
+
+/// How much of the end of a history file to scan for the latest snapshot
+const TAIL_BYTES: u64 = 256 * 1024;
+
+/// Size past which a history file is pruned to its newest half
+const MAX_FILE_BYTES: u64 = 16 * 1024 * 1024;
+
+/// How much recent history flakiness is scored from
+const RECENT_BYTES: u64 = 1024 * 1024;
+
+/// Append-only JSON lines file of snapshots for one repo
+pub struct SnapshotStore {
+    path: Option<PathBuf>,
+}
+
+impl SnapshotStore {
+    pub fn for_repo(repo: &str) -> Self {
+        Self {
+            path: data_dir().map(|d| d.join("history").join(format!("{}.jsonl", file_name(repo)))),
+        }
+    }
+
+    /// Append a snapshot unless nothing changed since the last recorded one
+    pub fn record(&self, status: &StackStatus) -> Result<()> {
+        let Some(ref path) = self.path else {
+            return Ok(());
+        };
+
+        let snapshot = Snapshot::from_status(status);
+        if snapshot.branches.is_empty() {
+            return Ok(());
+        }
+        if self.latest().is_some_and(|last| last.same_state(&snapshot)) {
+            return Ok(());
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", serde_json::to_string(&snapshot)?)?;
+
+        if file.metadata()?.len() > MAX_FILE_BYTES {
+            self.prune(path)?;
+        }
+        Ok(())
+    }
+
+    /// Drop the oldest snapshots, keeping the newest half of the size limit
+    fn prune(&self, path: &Path) -> Result<()> {
+        let Some(tail) = self.read_tail(MAX_FILE_BYTES / 2) else {
+            return Ok(());
+        };
+        let tmp = path.with_extension(format!("jsonl.{}.tmp", std::process::id()));
+        fs::write(&tmp, tail)?;
+        fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    /// Recorded snapshots, oldest first, from at most the last `MAX_FILE_BYTES` of the file
+    pub fn load(&self) -> Vec<Snapshot> {
+        self.load_tail(MAX_FILE_BYTES)
+    }
+
+    /// The most recent snapshots, oldest first, for scoring that shouldn't scan everything
+    pub fn recent(&self) -> Vec<Snapshot> {
+        self.load_tail(RECENT_BYTES)
+    }
+
+    /// Snapshots from the last `max_bytes` of the file, oldest first
+    fn load_tail(&self, max_bytes: u64) -> Vec<Snapshot> {
+        let Some(tail) = self.read_tail(max_bytes) else {
+            return Vec::new();
+        };
+
+        tail.lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()
+    }
+
+    /// Most recent snapshot, read from the end of the file
+    fn latest(&self) -> Option<Snapshot> {
+        self.read_tail(TAIL_BYTES)?
+            .lines()
+            .rev()
+            .find_map(|line| serde_json::from_str(line).ok())
+    }
+
+    /// Whole lines from the last `max_bytes` of the file
+    ///
+    /// The cut can land mid-line (and mid-UTF-8 char), so the partial first
+    /// line is skipped before decoding.
+    fn read_tail(&self, max_bytes: u64) -> Option<String> {
+        let mut file = fs::File::open(self.path.as_ref()?).ok()?;
+        let len = file.metadata().ok()?.len();
+        let start = len.saturating_sub(max_bytes);
+        file.seek(SeekFrom::Start(start)).ok()?;
+
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes).ok()?;
+        let lines = if start > 0 {
+            let newline = bytes.iter().position(|&b| b == b'\n')?;
+            &bytes[newline + 1..]
+        } else {
+            &bytes[..]
+        };
+        Some(String::from_utf8_lossy(lines).into_owned())
+    }
+}
+
+/// Age after which a cached branch status is no longer shown as current
+const STALE_AFTER: Duration = Duration::minutes(30);
+
+/// Latest known status of one branch, kept for instant reads by `prompt`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedBranch {
+    /// RFC 3339 fetch time
+    pub fetched_at: String,
+    pub pr: Option<u64>,
+    pub pr_state: Option<PrState>,
+    pub summary: Option<CheckSummary>,
+}
+
+impl CachedBranch {
+    /// Fetched too long ago to trust its check results
+    pub fn is_stale(&self, now: DateTime<Utc>) -> bool {
+        DateTime::parse_from_rfc3339(&self.fetched_at)
+            .map_or(true, |t| now - t.with_timezone(&Utc) > STALE_AFTER)
+    }
+}
+
+/// Per-checkout file of the latest status of every branch seen there
+pub struct BranchCache {
+    path: Option<PathBuf>,
+}
+
+impl BranchCache {
+    pub fn for_checkout(toplevel: &str) -> Self {
+        Self {
+            path: data_dir().map(|d| d.join("prompt").join(format!("{}.json", file_name(toplevel)))),
+        }
+    }
+
+    fn load(&self) -> HashMap<String, CachedBranch> {
+        self.path
+            .as_ref()
+            .and_then(|p| fs::read(p).ok())
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn get(&self, branch: &str) -> Option<CachedBranch> {
+        self.load().remove(branch)
+    }
+
+    /// Merge the branches of a fetched stack into the cache
+    pub fn update(&self, status: &StackStatus) -> Result<()> {
+        let Some(ref path) = self.path else {
+            return Ok(());
+        };
+
+        let fetched: Vec<(String, CachedBranch)> = status
+            .branches
+            .iter()
+            .filter(|b| !b.is_trunk)
+            .map(|b| {
+                let cached = CachedBranch {
+                    fetched_at: status.fetched_at.clone(),
+                    pr: b.pr,
+                    pr_state: b.pr_state,
+                    summary: b
+                        .summary
+                        .clone()
+                        .or_else(|| b.checks.as_deref().map(github::summarize_checks)),
+                };
+                (b.branch.clone(), cached)
+            })
+            .collect();
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // Re-read right before writing and never replace a newer entry, so
+        // concurrent writers (watch windows, the daemon) don't undo each other
+        let mut branches = self.load();
+        for (name, cached) in fetched {
+            let newer = branches.get(&name).is_some_and(|old| {
+                DateTime::parse_from_rfc3339(&old.fetched_at).ok()
+                    > DateTime::parse_from_rfc3339(&cached.fetched_at).ok()
+            });
+            if !newer {
+                branches.insert(name, cached);
+            }
+        }
+
+        // Write then rename, so a prompt never reads a half-written file; the
+        // temp name is per process so writers don't clobber each other's
+        let tmp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+        fs::write(&tmp, serde_json::to_vec(&branches)?)?;
+        fs::rename(tmp, path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use crate::BranchStatus;
+
+    fn check(name: &str, secs: u64, run: &str) -> Check {
+        Check {
+            duration_secs: Some(secs),
+            url: Some(run.to_string()),
+            ..testing::check(name, CheckStatus::Passed)
+        }
+    }
+
+    #[test]
+    fn test_record_dedupes_runs_and_takes_median() {
+        let mut history = DurationHistory::in_memory("acme/app");
+
+        assert!(history.record(&[check("test", 60, "r1"), check("test", 300, "r2")]));
+        assert!(!history.record(&[check("test", 60, "r1")]));
+        assert!(history.record(&[check("test", 90, "r3")]));
+        let failed = Check {
+            status: CheckStatus::Failed,
+            ..check("test", 5, "r4")
+        };
+        assert!(!history.record(&[failed]));
+
+        assert_eq!(history.expected_secs("test"), Some(90));
+        assert_eq!(history.expected_secs("lint"), None);
+    }
+
+    #[test]
+    fn test_latest_survives_cut_inside_multibyte_char() {
+        let path = std::env::temp_dir().join(format!("stack-status-{}.jsonl", std::process::id()));
+        let store = SnapshotStore {
+            path: Some(path.clone()),
+        };
+
+        let mut status = StackStatus::new();
+        status.branches = vec![BranchStatus {
+            pr: Some(1),
+            ..testing::branch("feature", testing::checks(&[("test", CheckStatus::Passed)]))
+        }];
+        let line = serde_json::to_string(&Snapshot::from_status(&status)).unwrap();
+        // "é" is two bytes; cut the tail one byte into one of them
+        fs::write(&path, format!("{}\n{}\n", "é".repeat(8), line)).unwrap();
+        let tail = store.read_tail(line.len() as u64 + 3);
+        let latest = store.latest();
+        fs::remove_file(&path).ok();
+
+        assert_eq!(tail.as_deref(), Some(format!("{}\n", line).as_str()));
+        assert_eq!(latest.map(|s| s.branches[0].branch.clone()).as_deref(), Some("feature"));
+    }
+}
@@ -0,0 +1,349 @@
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+
+/// A parsed output template
+///
+/// Syntax:
+/// - `{{ path }}` inserts a value, e.g. `{{ timestamp }}` or `{{ b.summary.passed }}`
+/// - `{% for b in branches %}…{% endfor %}` loops over an array
+/// - `{% if path %}`, `{% if not path %}`, `{% if path == "failed" %}` and `!=`,
+///   with optional `{% else %}`, closed by `{% endif %}`
+///
+/// A line holding only a `{% %}` tag is dropped, so block tags can sit on their own lines.
+#[derive(Debug)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug)]
+enum Node {
+    Text(String),
+    Var(String),
+    For {
+        var: String,
+        path: String,
+        body: Vec<Node>,
+    },
+    If {
+        cond: Condition,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+}
+
+#[derive(Debug)]
+enum Condition {
+    Truthy(String),
+    Not(String),
+    Eq(String, Value),
+    Ne(String, Value),
+}
+
+#[derive(Debug)]
+enum Token {
+    Text(String),
+    Var(String),
+    Tag(String),
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut tokens = tokenize(source)?.into_iter();
+        let (nodes, end) = parse_nodes(&mut tokens)?;
+        if let Some(tag) = end {
+            bail!("unexpected {{% {} %}}", tag);
+        }
+        Ok(Self { nodes })
+    }
+
+    pub fn render(&self, root: &Value) -> String {
+        let mut out = String::new();
+        let mut scope = Vec::new();
+        render_nodes(&self.nodes, root, &mut scope, &mut out);
+        out
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+    // Whether only whitespace has been emitted since the last newline
+    let mut line_blank = true;
+
+    while let Some(start) = rest.find("{{").into_iter().chain(rest.find("{%")).min() {
+        let is_tag = rest[start..].starts_with("{%");
+        let close = if is_tag { "%}" } else { "}}" };
+        let Some(len) = rest[start + 2..].find(close) else {
+            bail!("unclosed {} in template", &rest[start..start + 2]);
+        };
+
+        let mut text = &rest[..start];
+        let inner = rest[start + 2..start + 2 + len].trim().to_string();
+        rest = &rest[start + 2 + len + 2..];
+
+        let line_start = text.rfind('\n').map(|i| i + 1);
+        line_blank = text[line_start.unwrap_or(0)..].trim().is_empty()
+            && (line_start.is_some() || line_blank);
+
+        // Drop the line around a block tag that stands alone on it
+        let after = rest.find('\n').map(|i| &rest[..i]).unwrap_or(rest);
+        if is_tag && line_blank && after.trim().is_empty() {
+            text = &text[..line_start.unwrap_or(0)];
+            rest = rest.find('\n').map(|i| &rest[i + 1..]).unwrap_or("");
+        } else {
+            line_blank = false;
+        }
+
+        if !text.is_empty() {
+            tokens.push(Token::Text(text.to_string()));
+        }
+        tokens.push(if is_tag {
+            Token::Tag(inner)
+        } else {
+            Token::Var(inner)
+        });
+    }
+
+    if !rest.is_empty() {
+        tokens.push(Token::Text(rest.to_string()));
+    }
+    Ok(tokens)
+}
+
+/// Parse nodes until the end of input or a closing tag, which is returned
+fn parse_nodes(tokens: &mut impl Iterator<Item = Token>) -> Result<(Vec<Node>, Option<String>)> {
+    let mut nodes = Vec::new();
+
+    while let Some(token) = tokens.next() {
+        let tag = match token {
+            Token::Text(text) => {
+                nodes.push(Node::Text(text));
+                continue;
+            }
+            Token::Var(path) => {
+                nodes.push(Node::Var(path));
+                continue;
+            }
+            Token::Tag(tag) => tag,
+        };
+
+        let words: Vec<&str> = tag.split_whitespace().collect();
+        match words.as_slice() {
+            ["for", var, "in", path] => {
+                let (body, end) = parse_nodes(tokens)?;
+                if end.as_deref() != Some("endfor") {
+                    bail!("{{% {} %}} is missing {{% endfor %}}", tag);
+                }
+                nodes.push(Node::For {
+                    var: var.to_string(),
+                    path: path.to_string(),
+                    body,
+                });
+            }
+            ["if", ..] => {
+                let cond = parse_condition(&tag["if".len()..])
+                    .with_context(|| format!("in {{% {} %}}", tag))?;
+                let (then, mut end) = parse_nodes(tokens)?;
+                let mut otherwise = Vec::new();
+                if end.as_deref() == Some("else") {
+                    (otherwise, end) = parse_nodes(tokens)?;
+                }
+                if end.as_deref() != Some("endif") {
+                    bail!("{{% {} %}} is missing {{% endif %}}", tag);
+                }
+                nodes.push(Node::If {
+                    cond,
+                    then,
+                    otherwise,
+                });
+            }
+            ["endfor"] | ["endif"] | ["else"] => return Ok((nodes, Some(tag))),
+            _ => bail!("unknown template tag {{% {} %}}", tag),
+        }
+    }
+
+    Ok((nodes, None))
+}
+
+fn parse_condition(expr: &str) -> Result<Condition> {
+    let expr = expr.trim();
+    if let Some((at, op)) = find_operator(expr) {
+        let path = expr[..at].trim().to_string();
+        let literal = expr[at + op.len()..].trim();
+        let value = match literal.strip_prefix('"').and_then(|l| l.strip_suffix('"')) {
+            Some(s) => Value::String(s.to_string()),
+            None => serde_json::from_str(literal)
+                .with_context(|| format!("invalid literal {}", literal))?,
+        };
+        return Ok(if op == "!=" {
+            Condition::Ne(path, value)
+        } else {
+            Condition::Eq(path, value)
+        });
+    }
+
+    match expr.strip_prefix("not ") {
+        Some(path) => Ok(Condition::Not(path.trim().to_string())),
+        None if !expr.is_empty() => Ok(Condition::Truthy(expr.to_string())),
+        None => bail!("empty condition"),
+    }
+}
+
+/// Position of the first `==` or `!=` outside a quoted string
+fn find_operator(expr: &str) -> Option<(usize, &'static str)> {
+    let mut quoted = false;
+    for (i, c) in expr.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            _ if quoted => {}
+            _ if expr[i..].starts_with("==") => return Some((i, "==")),
+            _ if expr[i..].starts_with("!=") => return Some((i, "!=")),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn render_nodes<'a>(
+    nodes: &[Node],
+    root: &'a Value,
+    scope: &mut Vec<(String, &'a Value)>,
+    out: &mut String,
+) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Var(path) => match lookup(path, root, scope) {
+                Some(Value::String(s)) => out.push_str(s),
+                Some(Value::Null) | None => {}
+                Some(value) => out.push_str(&value.to_string()),
+            },
+            Node::For { var, path, body } => {
+                let Some(Value::Array(items)) = lookup(path, root, scope) else {
+                    continue;
+                };
+                for item in items {
+                    scope.push((var.clone(), item));
+                    render_nodes(body, root, scope, out);
+                    scope.pop();
+                }
+            }
+            Node::If {
+                cond,
+                then,
+                otherwise,
+            } => {
+                let branch = if evaluate(cond, root, scope) {
+                    then
+                } else {
+                    otherwise
+                };
+                render_nodes(branch, root, scope, out);
+            }
+        }
+    }
+}
+
+fn evaluate(cond: &Condition, root: &Value, scope: &[(String, &Value)]) -> bool {
+    match cond {
+        Condition::Truthy(path) => truthy(lookup(path, root, scope)),
+        Condition::Not(path) => !truthy(lookup(path, root, scope)),
+        Condition::Eq(path, value) => lookup(path, root, scope) == Some(value),
+        Condition::Ne(path, value) => lookup(path, root, scope) != Some(value),
+    }
+}
+
+fn truthy(value: Option<&Value>) -> bool {
+    match value {
+        None | Some(Value::Null) | Some(Value::Bool(false)) => false,
+        Some(Value::String(s)) => !s.is_empty(),
+        Some(Value::Array(a)) => !a.is_empty(),
+        Some(Value::Number(n)) => n.as_f64() != Some(0.0),
+        Some(_) => true,
+    }
+}
+
+/// Resolve a dotted path against loop variables first, then the root object
+fn lookup<'a>(path: &str, root: &'a Value, scope: &[(String, &'a Value)]) -> Option<&'a Value> {
+    let mut parts = path.split('.');
+    let first = parts.next()?;
+
+    let mut value = match scope.iter().rev().find(|(name, _)| name == first) {
+        Some((_, value)) => *value,
+        None => root.get(first)?,
+    };
+    for part in parts {
+        value = match part.parse::<usize>() {
+            Ok(index) => value.get(index)?,
+            Err(_) => value.get(part)?,
+        };
+    }
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn status() -> Value {
+        json!({
+            "timestamp": "12:34:56",
+            "branches": [
+                {
+                    "branch": "feature-b",
+                    "pr": 2,
+                    "summary": {"overall": "failed", "failed": 1},
+                    "checks": [
+                        {"name": "lint", "status": "passed"},
+                        {"name": "test", "status": "failed", "url": "https://ci/2"}
+                    ]
+                },
+                {"branch": "feature-a", "pr": 1, "summary": {"overall": "passed", "failed": 0}, "checks": []},
+                {"branch": "main", "is_trunk": true, "pr": null}
+            ]
+        })
+    }
+
+    #[test]
+    fn test_loops_and_conditionals() {
+        let template = Template::parse(
+            "Stack at {{ timestamp }}\n\
+             {% for b in branches %}\n\
+             {% if not b.is_trunk %}\n\
+             #{{ b.pr }} {{ b.branch }}: {% if b.summary.overall == \"failed\" %}✗{% else %}✓{% endif %}\n\
+             {% for c in b.checks %}\n\
+             {% if c.status != \"passed\" %}\n\
+             \x20 {{ c.name }} {{ c.url }}\n\
+             {% endif %}\n\
+             {% endfor %}\n\
+             {% endif %}\n\
+             {% endfor %}\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            template.render(&status()),
+            "Stack at 12:34:56\n#2 feature-b: ✗\n  test https://ci/2\n#1 feature-a: ✓\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Template::parse("{% for b in branches %}").is_err());
+        assert!(Template::parse("{% if x %}{% endfor %}").is_err());
+        assert!(Template::parse("{{ unclosed").is_err());
+        assert!(Template::parse("{% while x %}{% endwhile %}").is_err());
+    }
+
+    #[test]
+    fn test_operators_inside_strings() {
+        let template = Template::parse(
+            "{% if branch != \"a==b\" %}ne{% endif %}{% if branch == \"x!=y\" %}eq{% endif %}",
+        )
+        .unwrap();
+
+        assert_eq!(template.render(&json!({"branch": "x!=y"})), "neeq");
+        assert_eq!(template.render(&json!({"branch": "a==b"})), "");
+    }
+}
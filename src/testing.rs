@@ -0,0 +1,48 @@
+//! Builders shared by the test modules
+
+use crate::github::{self, Check, CheckStatus};
+use crate::store::{BranchSnapshot, Snapshot};
+use crate::BranchStatus;
+
+/// A check with only a name and status; set other fields with `..check(..)`
+pub fn check(name: &str, status: CheckStatus) -> Check {
+    Check {
+        name: name.to_string(),
+        status,
+        conclusion: None,
+        started_at: None,
+        duration_secs: None,
+        expected_secs: None,
+        flaky: None,
+        url: None,
+    }
+}
+
+pub fn checks(statuses: &[(&str, CheckStatus)]) -> Vec<Check> {
+    statuses.iter().map(|&(name, status)| check(name, status)).collect()
+}
+
+/// A branch with the given checks and their summary; set other fields with `..branch(..)`
+pub fn branch(name: &str, checks: Vec<Check>) -> BranchStatus {
+    BranchStatus {
+        branch: name.to_string(),
+        summary: Some(github::summarize_checks(&checks)),
+        checks: Some(checks),
+        ..Default::default()
+    }
+}
+
+/// A snapshot of a single branch `feature` (PR #1) at the given commit
+pub fn snapshot(fetched_at: &str, sha: &str, checks: Vec<Check>) -> Snapshot {
+    Snapshot {
+        fetched_at: fetched_at.to_string(),
+        branches: vec![BranchSnapshot {
+            branch: "feature".to_string(),
+            pr: Some(1),
+            pr_state: None,
+            head_sha: Some(sha.to_string()),
+            author: None,
+            checks,
+        }],
+    }
+}
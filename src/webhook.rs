@@ -0,0 +1,252 @@
+use crate::github::CheckStatus;
+use crate::StackStatus;
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use serde_json::json;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+/// Message used when no template is configured
+pub const DEFAULT_TEMPLATE: &str = "{icon} Stack for {branch} is {status}: {summary}";
+
+/// Chat system the webhook belongs to, which decides the payload shape
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookKind {
+    /// `{"text": ...}`
+    #[default]
+    Slack,
+    /// `{"content": ...}`
+    Discord,
+    /// Office 365 connector `MessageCard`
+    Teams,
+}
+
+/// `[webhook]` section of the config file
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct WebhookConfig {
+    pub url: Option<String>,
+    #[serde(default)]
+    pub kind: WebhookKind,
+    /// Message template; see `render_message` for placeholders
+    pub template: Option<String>,
+}
+
+/// Posts the stack's aggregate status to a chat webhook
+#[derive(Debug, Clone)]
+pub struct Webhook {
+    pub url: String,
+    pub kind: WebhookKind,
+    pub template: String,
+}
+
+impl Webhook {
+    pub fn from_config(config: WebhookConfig) -> Option<Self> {
+        Some(Self {
+            url: config.url?,
+            kind: config.kind,
+            template: config.template.unwrap_or_else(|| DEFAULT_TEMPLATE.to_string()),
+        })
+    }
+
+    /// POST the current status through `curl`
+    pub async fn send(&self, status: &StackStatus) -> Result<()> {
+        let message = render_message(&self.template, status);
+        let body = serde_json::to_string(&payload(self.kind, &message))?;
+        // URL and body go through a config on stdin: webhook URLs are secrets, and argv isn't
+        let config = format!(
+            "url = {}\ndata-binary = {}\n",
+            config_string(&self.url),
+            config_string(&body)
+        );
+
+        let mut child = Command::new("curl")
+            .args(["-fsS", "-X", "POST", "-H", "Content-Type: application/json"])
+            .args(["--config", "-"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("running curl")?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(config.as_bytes()).await?;
+        }
+
+        let output = child.wait_with_output().await?;
+        if !output.status.success() {
+            bail!(
+                "webhook POST failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Quote a value for a curl config file
+fn config_string(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+        .replace('\t', "\\t");
+    format!("\"{}\"", escaped)
+}
+
+/// JSON body understood by each chat system's incoming webhooks
+pub fn payload(kind: WebhookKind, message: &str) -> serde_json::Value {
+    match kind {
+        WebhookKind::Slack => json!({ "text": message }),
+        WebhookKind::Discord => json!({ "content": message }),
+        WebhookKind::Teams => json!({
+            "@type": "MessageCard",
+            "@context": "https://schema.org/extensions",
+            "summary": message,
+            "text": message,
+        }),
+    }
+}
+
+/// Fill in a message template
+///
+/// Placeholders: `{icon}`, `{status}`, `{branch}` (the focus branch),
+/// `{passed}`, `{failed}`, `{running}`, `{total}` (branch counts),
+/// `{failing}` (failing branch names) and `{summary}`.
+pub fn render_message(template: &str, status: &StackStatus) -> String {
+    let overall = status.overall();
+    let branches: Vec<_> = status
+        .branches
+        .iter()
+        .filter(|b| !b.is_trunk && b.summary.is_some())
+        .collect();
+    let with = |s: CheckStatus| {
+        branches
+            .iter()
+            .filter(|b| b.summary.as_ref().is_some_and(|sum| sum.overall == s))
+            .count()
+    };
+
+    let failing: Vec<String> = branches
+        .iter()
+        .filter(|b| b.summary.as_ref().is_some_and(|s| s.overall == CheckStatus::Failed))
+        .map(|b| match b.pr {
+            Some(n) => format!("{} (#{})", b.branch, n),
+            None => b.branch.clone(),
+        })
+        .collect();
+
+    let (passed, failed, running) = (
+        with(CheckStatus::Passed),
+        with(CheckStatus::Failed),
+        with(CheckStatus::Running),
+    );
+    let mut summary = format!("{}/{} branches passed", passed, branches.len());
+    if !failing.is_empty() {
+        summary.push_str(&format!(", failing: {}", failing.join(", ")));
+    } else if running > 0 {
+        summary.push_str(&format!(", {} running", running));
+    }
+
+    let (icon, word) = match overall {
+        CheckStatus::Passed => ("✅", "passing"),
+        CheckStatus::Failed => ("❌", "failing"),
+        CheckStatus::Running | CheckStatus::Queued => ("⏳", "running"),
+        _ => ("❔", "unknown"),
+    };
+    let focus = status
+        .branches
+        .iter()
+        .find(|b| b.is_focus)
+        .map(|b| b.branch.as_str())
+        .unwrap_or("");
+
+    template
+        .replace("{icon}", icon)
+        .replace("{status}", word)
+        .replace("{branch}", focus)
+        .replace("{passed}", &passed.to_string())
+        .replace("{failed}", &failed.to_string())
+        .replace("{running}", &running.to_string())
+        .replace("{total}", &branches.len().to_string())
+        .replace("{failing}", &failing.join(", "))
+        .replace("{summary}", &summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use crate::BranchStatus;
+    use tokio::io::AsyncReadExt;
+
+    fn status() -> StackStatus {
+        let branch = |name: &str, pr, check: CheckStatus| BranchStatus {
+            is_focus: name == "feature-b",
+            pr: Some(pr),
+            ..testing::branch(name, vec![testing::check("test", check)])
+        };
+
+        let mut status = StackStatus::new();
+        status.branches = vec![
+            branch("feature-b", 2, CheckStatus::Failed),
+            branch("feature-a", 1, CheckStatus::Passed),
+        ];
+        status
+    }
+
+    #[test]
+    fn test_render_message() {
+        assert_eq!(
+            render_message(DEFAULT_TEMPLATE, &status()),
+            "❌ Stack for feature-b is failing: 1/2 branches passed, failing: feature-b (#2)"
+        );
+        assert_eq!(render_message("{failed} of {total}", &status()), "1 of 2");
+    }
+
+    #[tokio::test]
+    async fn test_send_posts_to_local_listener() {
+        if std::process::Command::new("curl").arg("--version").output().is_err() {
+            eprintln!("skipping: curl not installed");
+            return;
+        }
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 4096];
+            // Read until the JSON body has arrived, giving up rather than hanging the test
+            let read = async {
+                while !request.ends_with(b"}") {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                }
+            };
+            tokio::time::timeout(std::time::Duration::from_secs(10), read)
+                .await
+                .expect("timed out reading the webhook request");
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                .await
+                .unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let webhook = Webhook {
+            url: format!("http://{}/hook", addr),
+            kind: WebhookKind::Discord,
+            template: "{status} \"now\"".to_string(),
+        };
+        webhook.send(&status()).await.unwrap();
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("POST /hook HTTP/1.1"));
+        assert!(request.ends_with(r#"{"content":"failing \"now\""}"#));
+    }
+}